@@ -0,0 +1,4 @@
+//! Implement me
+//!
+//! Check that device.info(DeviceInfo::OpenclCVersion == "OpenCL C 1.2") or greater
+//! Perhaps add something to parse the OpenCL C version into a number.
@@ -0,0 +1,120 @@
+//! On-disk cache of compiled OpenCL program binaries
+//!
+//! Binaries are stored in one file per program, named after a hash of the device name, driver
+//! version, build options and program source. A binary that fails to load, e.g. after a driver update which did
+//! not change the version string, is replaced by compiling the source again.
+
+extern crate ocl;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{ Hash, Hasher };
+use std::path::PathBuf;
+use std::sync::{ LazyLock, Mutex };
+use std::fs;
+
+/// Options every program is built with, argument info lets KernelCall check argument types
+const BUILD_OPTIONS: &str = "-cl-kernel-arg-info";
+
+/// Directory of the cache, None if the cache is disabled
+pub(crate) static CACHE_DIR: LazyLock<Mutex<Option<PathBuf>>> = LazyLock::new(|| Mutex::new(Some(
+    match ::std::env::var_os("LINEAR_ALGEBRA_CACHE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => ::std::env::temp_dir().join("linear_algebra_kernels"),
+    }
+)));
+
+/// Build program from src for device, loading it from the cache if possible
+pub(crate) fn build_program(context: &ocl::Context, device: ocl::Device, src: &str) -> ocl::Result<ocl::Program> {
+    let path = CACHE_DIR.lock().unwrap().as_ref().map(|dir| dir.join(cache_file_name(device, src)));
+
+    if let Some(ref path) = path {
+        if let Ok(binary) = fs::read(path) {
+            if let Ok(program) = program_from_binary(context, device, &binary) {
+                return Ok(program);
+            }
+        }
+    }
+
+    let program = ocl::Program::builder()
+        .src(src)
+        .devices(device)
+        .cmplr_opt(BUILD_OPTIONS)
+        .build(context)?;
+
+    // The cache is only an optimization, failing to write it is not an error
+    if let Some(ref path) = path {
+        if let Ok(binary) = program_binary(&program) {
+            let _ = fs::create_dir_all(path.parent().unwrap())
+                .and_then(|_| fs::write(path, &binary));
+        }
+    }
+
+    Ok(program)
+}
+
+/// Hash the device name and driver version, which identify the binaries a device can load
+pub(crate) fn hash_device<H: Hasher>(device: ocl::Device, hasher: &mut H) {
+    use ocl::enums::DeviceInfo;
+
+    device.name().hash(hasher);
+    device.info(DeviceInfo::DriverVersion).to_string().hash(hasher);
+}
+
+fn cache_file_name(device: ocl::Device, src: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    hash_device(device, &mut hasher);
+    BUILD_OPTIONS.hash(&mut hasher);
+    src.hash(&mut hasher);
+
+    format!("{:016x}.bin", hasher.finish())
+}
+
+fn program_from_binary(context: &ocl::Context, device: ocl::Device, binary: &[u8]) -> ocl::Result<ocl::Program> {
+    use std::ffi::CString;
+
+    // ocl can not create a Program from a binary, so a trivial program is built and its core
+    // replaced by the one created from the binary
+    let mut program = ocl::Program::builder()
+        .src("kernel void linear_algebra_placeholder() {}")
+        .devices(device)
+        .build(context)?;
+
+    *program = ocl::core::create_program_with_binary(context, &[device], &[binary])?;
+    ocl::core::build_program(&program, Some(&[device]), &CString::new(BUILD_OPTIONS).unwrap(), None, None)?;
+
+    Ok(program)
+}
+
+/// Get the binary of a program built for a single device
+fn program_binary(program: &ocl::Program) -> ocl::Result<Vec<u8>> {
+    use ocl::ffi::{ clGetProgramInfo, CL_PROGRAM_BINARY_SIZES, CL_PROGRAM_BINARIES };
+    use std::mem::size_of;
+    use std::ptr;
+
+    let mut size: usize = 0;
+    let errcode = unsafe { clGetProgramInfo(
+        program.as_ptr(),
+        CL_PROGRAM_BINARY_SIZES,
+        size_of::<usize>(),
+        &mut size as *mut usize as *mut _,
+        ptr::null_mut(),
+    ) };
+    if errcode != 0 || size == 0 {
+        return Err(format!("Failed to get program binary size: {}", errcode).into());
+    }
+
+    let mut binary = vec![0u8; size];
+    let mut binary_ptr = binary.as_mut_ptr();
+    let errcode = unsafe { clGetProgramInfo(
+        program.as_ptr(),
+        CL_PROGRAM_BINARIES,
+        size_of::<*mut u8>(),
+        &mut binary_ptr as *mut *mut u8 as *mut _,
+        ptr::null_mut(),
+    ) };
+    if errcode != 0 {
+        return Err(format!("Failed to get program binary: {}", errcode).into());
+    }
+
+    Ok(binary)
+}
@@ -0,0 +1,422 @@
+//! Typed calls of user-defined kernels
+//!
+//! A KernelCall binds vectors, matrices, scalars and local memory to the arguments of a kernel
+//! registered with add_kernel_src() by position, checks them and runs the kernel:
+//!
+//! ```ignore
+//! add_kernel_src("kernel void {T}_axpy(global {T}* C, global {T}* X, global {T}* Y, {T} a) {
+//!     C[i] = a * X[i] + Y[i];
+//! }");
+//! let c: Vector<f32> = context.kernel_call::<f32>("axpy")
+//!     .output(x.len())
+//!     .arg(&x)
+//!     .arg(&y)
+//!     .scalar(2.0)
+//!     .run_vector()?;
+//! ```
+//!
+//! The kernel name is given without the `{T}_` prefix, the kernel is compiled for T.
+
+extern crate ocl;
+
+use std::marker::PhantomData;
+
+use context::{ Context, requires_opencl };
+use error::{ Error, Result };
+use matrix::Matrix;
+use traits::Parameter;
+use vector::{ Data, Vector };
+
+/// Argument bound to a kernel, type erased over its element type
+trait Bound {
+    fn set(&self, kernel: &mut ocl::Kernel, idx: u32) -> Result<()>;
+
+    /// Vector or matrix bound to the argument, None for scalars and local memory
+    fn buffer(&self) -> Option<BufferInfo<'_>>;
+
+    /// Element type of a scalar argument, None for other arguments
+    fn scalar_type(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns true if the kernel writes to the argument
+    fn is_written(&self) -> bool {
+        false
+    }
+
+    /// Called with the event of the kernel, records it on the vector or matrix bound
+    fn enqueued(&mut self, _event: &ocl::Event) {}
+}
+
+struct BufferInfo<'a> {
+    len: usize,
+    ty: &'static str,
+    context: &'a Context,
+    /// Events the kernel has to wait for, those of pending reads as well if it writes
+    events: ocl::EventList,
+    on_host: bool,
+}
+
+fn buffer_info<U: Parameter>(v: &Vector<U>, written: bool) -> BufferInfo<'_> {
+    BufferInfo {
+        len: v.len(),
+        ty: U::type_to_str(),
+        context: &v.context,
+        events: if written { v.write_wait_list() } else { v.events.clone() },
+        on_host: match v.data {
+            Data::Host(_) => true,
+            Data::Ocl(_) => false,
+        },
+    }
+}
+
+fn set_buffer<U: Parameter>(v: &Vector<U>, kernel: &mut ocl::Kernel, idx: u32) -> Result<()> {
+    unsafe {
+        Ok(kernel.set_arg_unchecked(idx, ocl::enums::KernelArg::Mem::<U>(v.buf().core()))?)
+    }
+}
+
+struct Input<'a, U: Parameter + 'a>(&'a Vector<U>);
+
+impl<'a, U: Parameter> Bound for Input<'a, U> {
+    fn set(&self, kernel: &mut ocl::Kernel, idx: u32) -> Result<()> {
+        set_buffer(self.0, kernel, idx)
+    }
+    fn buffer(&self) -> Option<BufferInfo<'_>> {
+        Some(buffer_info(self.0, false))
+    }
+    fn enqueued(&mut self, event: &ocl::Event) {
+        Vector::add_read_event(&[self.0], event);
+    }
+}
+
+struct InOut<'a, U: Parameter + 'a>(&'a mut Vector<U>);
+
+impl<'a, U: Parameter> Bound for InOut<'a, U> {
+    fn set(&self, kernel: &mut ocl::Kernel, idx: u32) -> Result<()> {
+        set_buffer(self.0, kernel, idx)
+    }
+    fn buffer(&self) -> Option<BufferInfo<'_>> {
+        Some(buffer_info(self.0, true))
+    }
+    fn is_written(&self) -> bool {
+        true
+    }
+    fn enqueued(&mut self, event: &ocl::Event) {
+        self.0.set_event(event.clone());
+    }
+}
+
+struct Scalar<U: Parameter>(U);
+
+impl<U: Parameter> Bound for Scalar<U> {
+    fn set(&self, kernel: &mut ocl::Kernel, idx: u32) -> Result<()> {
+        unsafe {
+            Ok(kernel.set_arg_unchecked(idx, ocl::enums::KernelArg::Scalar(self.0))?)
+        }
+    }
+    fn buffer(&self) -> Option<BufferInfo<'_>> {
+        None
+    }
+    fn scalar_type(&self) -> Option<&'static str> {
+        Some(U::type_to_str())
+    }
+}
+
+struct Local<U: Parameter>(usize, PhantomData<U>);
+
+impl<U: Parameter> Bound for Local<U> {
+    fn set(&self, kernel: &mut ocl::Kernel, idx: u32) -> Result<()> {
+        unsafe {
+            Ok(kernel.set_arg_unchecked(idx, ocl::enums::KernelArg::Local::<U>(&self.0))?)
+        }
+    }
+    fn buffer(&self) -> Option<BufferInfo<'_>> {
+        None
+    }
+}
+
+enum Slot<'a> {
+    Bound(Box<dyn Bound + 'a>),
+    /// Length of a new vector, or its rows and columns if it is a matrix
+    Output(usize, Option<(usize, usize)>),
+}
+
+/// Call of a user-defined kernel compiled for T, created by Context::kernel_call()
+///
+/// Arguments are bound in the order of the kernel parameters. Errors are reported by run(),
+/// which fails if
+///
+/// * the number of bound arguments differs from the number of kernel parameters
+/// * a vector, matrix or scalar has another element type than its parameter, if the OpenCL
+///   implementation reports parameter types
+/// * a vector or matrix belongs to another context
+/// * a vector or matrix has another length than the global work size, unless the global work
+///   size was set explicitly
+///
+/// The global work size defaults to the length of the first output, or of the first vector or
+/// matrix the kernel writes to, or of the first vector or matrix.
+pub struct KernelCall<'a, T: Parameter> {
+    context: Context,
+    name: String,
+    slots: Vec<Slot<'a>>,
+    global_work_size: Option<usize>,
+    local_work_size: Option<usize>,
+    element_type: PhantomData<T>,
+}
+
+/// Outputs of a kernel call in the order they were bound
+pub struct Outputs<T: Parameter> {
+    outputs: Vec<Output<T>>,
+}
+
+/// Output vector and the shape of outputs bound as matrices
+type Output<T> = (Vector<T>, Option<(usize, usize)>);
+
+impl<T: Parameter> Outputs<T> {
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Output vectors, outputs bound as matrices are flattened in row major order
+    pub fn into_vectors(self) -> Vec<Vector<T>> {
+        self.outputs.into_iter().map(|(v, _)| v).collect()
+    }
+
+    /// Output matrices, fails with Error::ShapeMismatch if an output was bound as a vector
+    pub fn into_matrices(self) -> Result<Vec<Matrix<T>>> {
+        self.outputs.into_iter().map(|(v, shape)| match shape {
+            Some((row_count, col_count)) => Ok(Matrix::from_data(v, row_count, col_count)),
+            None => Err(Error::ShapeMismatch("Output was bound as a vector, not a matrix".to_owned())),
+        }).collect()
+    }
+}
+
+impl<'a, T: Parameter> KernelCall<'a, T> {
+    pub(crate) fn new(context: &Context, kernel_name: &str) -> KernelCall<'a, T> {
+        KernelCall {
+            context: context.clone(),
+            name: kernel_name.to_owned(),
+            slots: Vec::new(),
+            global_work_size: None,
+            local_work_size: None,
+            element_type: PhantomData,
+        }
+    }
+
+    fn bind<B: Bound + 'a>(mut self, arg: B) -> Self {
+        self.slots.push(Slot::Bound(Box::new(arg)));
+        self
+    }
+
+    /// Bind a vector the kernel only reads
+    pub fn arg<U: Parameter>(self, v: &'a Vector<U>) -> Self {
+        self.bind(Input(v))
+    }
+
+    /// Bind a vector the kernel writes to, later operations on it wait for the kernel
+    pub fn arg_mut<U: Parameter>(self, v: &'a mut Vector<U>) -> Self {
+        self.bind(InOut(v))
+    }
+
+    /// Bind a matrix the kernel only reads, its elements in row major order
+    pub fn matrix<U: Parameter>(self, m: &'a Matrix<U>) -> Self {
+        self.bind(Input(&m.data))
+    }
+
+    /// Bind a matrix the kernel writes to, see arg_mut()
+    pub fn matrix_mut<U: Parameter>(self, m: &'a mut Matrix<U>) -> Self {
+        self.bind(InOut(&mut m.data))
+    }
+
+    /// Bind a scalar
+    pub fn scalar<U: Parameter>(self, value: U) -> Self {
+        self.bind(Scalar(value))
+    }
+
+    /// Bind local memory of len elements of type U
+    pub fn local<U: Parameter>(self, len: usize) -> Self {
+        self.bind(Local::<U>(len, PhantomData))
+    }
+
+    /// Bind a new vector of len elements the kernel writes to, returned by run()
+    ///
+    /// The vector is not initialized, the kernel has to write every element.
+    pub fn output(mut self, len: usize) -> Self {
+        self.slots.push(Slot::Output(len, None));
+        self
+    }
+
+    /// Bind a new matrix the kernel writes to, see output()
+    pub fn output_matrix(mut self, row_count: usize, col_count: usize) -> Self {
+        self.slots.push(Slot::Output(row_count * col_count, Some((row_count, col_count))));
+        self
+    }
+
+    /// Set the number of work items, disables the length check of vectors and matrices
+    pub fn global_work_size(mut self, size: usize) -> Self {
+        self.global_work_size = Some(size);
+        self
+    }
+
+    /// Set the work group size, chosen by OpenCL by default
+    pub fn local_work_size(mut self, size: usize) -> Self {
+        self.local_work_size = Some(size);
+        self
+    }
+
+    /// Same as run() but returns the only output, fails with Error::ShapeMismatch if there is
+    /// not exactly one
+    pub fn run_vector(self) -> Result<Vector<T>> {
+        let mut outputs = self.run()?.into_vectors();
+        match outputs.len() {
+            1 => Ok(outputs.pop().unwrap()),
+            n => Err(Error::ShapeMismatch(format!("{} outputs, expected 1", n))),
+        }
+    }
+
+    /// Same as run_vector() but returns the output bound with output_matrix()
+    pub fn run_matrix(self) -> Result<Matrix<T>> {
+        let mut outputs = self.run()?.into_matrices()?;
+        match outputs.len() {
+            1 => Ok(outputs.pop().unwrap()),
+            n => Err(Error::ShapeMismatch(format!("{} outputs, expected 1", n))),
+        }
+    }
+
+    /// Check the arguments and enqueue the kernel, returns the outputs
+    pub fn run(mut self) -> Result<Outputs<T>> {
+        if self.context.is_native() {
+            return Err(requires_opencl());
+        }
+
+        let mut kernel = self.context.try_create_kernel::<T>(&format!("{}_{}", T::type_to_str(), self.name))?;
+        if kernel.num_args() as usize != self.slots.len() {
+            return Err(Error::ShapeMismatch(format!("{} arguments bound to kernel {} taking {}",
+                self.slots.len(), self.name, kernel.num_args())));
+        }
+
+        let global_work_size = match self.global_work_size {
+            Some(size) => size,
+            None => {
+                let size = self.default_work_size()
+                    .ok_or_else(|| Error::ShapeMismatch("No vector or matrix to take the global work size from".to_owned()))?;
+                self.check_lengths(size)?;
+                size
+            },
+        };
+
+        let mut outputs = Vec::new();
+        let mut wait_list = ocl::EventList::new();
+        for (idx, slot) in self.slots.iter().enumerate() {
+            let idx = idx as u32;
+            match *slot {
+                Slot::Bound(ref arg) => {
+                    if let Some(info) = arg.buffer() {
+                        self.context.check_same(info.context)?;
+                        if info.on_host {
+                            return Err(requires_opencl());
+                        }
+                        check_arg_type(&kernel, idx, info.ty, true)?;
+                        for e in info.events.iter() {
+                            wait_list.push(e.clone());
+                        }
+                    }
+                    if let Some(ty) = arg.scalar_type() {
+                        check_arg_type(&kernel, idx, ty, false)?;
+                    }
+                    arg.set(&mut kernel, idx)?;
+                },
+                Slot::Output(len, shape) => {
+                    check_arg_type(&kernel, idx, T::type_to_str(), true)?;
+                    let v = unsafe { Vector::<T>::try_uninitialized_in(len, &self.context)? };
+                    for e in v.write_wait_list().iter() {
+                        wait_list.push(e.clone());
+                    }
+                    set_buffer(&v, &mut kernel, idx)?;
+                    outputs.push((v, shape));
+                },
+            }
+        }
+
+        let mut event = ocl::Event::empty();
+        unsafe {
+            let cmd = kernel.cmd()
+                .queue(self.context.cl_queue()?)
+                .ewait(&wait_list)
+                .enew(&mut event)
+                .gws(global_work_size);
+            match self.local_work_size {
+                Some(size) => cmd.lws(size).enq()?,
+                None => cmd.enq()?,
+            }
+        }
+        self.context.profile_kernel(&kernel, &event);
+
+        for slot in self.slots.iter_mut() {
+            if let Slot::Bound(ref mut arg) = *slot {
+                arg.enqueued(&event);
+            }
+        }
+        for &mut (ref mut v, _) in outputs.iter_mut() {
+            v.set_event(event.clone());
+        }
+
+        Ok(Outputs { outputs })
+    }
+
+    /// Length of the first output, or of the first argument written to, or of the first buffer
+    fn default_work_size(&self) -> Option<usize> {
+        let find = |written_only: bool| self.slots.iter().filter_map(|slot| match *slot {
+            Slot::Bound(ref arg) if arg.is_written() || !written_only => arg.buffer().map(|info| info.len),
+            _ => None,
+        }).next();
+
+        let output = self.slots.iter().filter_map(|slot| match *slot {
+            Slot::Output(len, _) => Some(len),
+            Slot::Bound(_) => None,
+        }).next();
+
+        output.or_else(|| find(true)).or_else(|| find(false))
+    }
+
+    fn check_lengths(&self, size: usize) -> Result<()> {
+        for slot in &self.slots {
+            let len = match *slot {
+                Slot::Bound(ref arg) => match arg.buffer() {
+                    Some(info) => info.len,
+                    None => continue,
+                },
+                Slot::Output(len, _) => len,
+            };
+            if len != size {
+                return Err(Error::shape_mismatch(size, len));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fails with Error::InvalidArgument if the OpenCL implementation reports another type for
+/// parameter idx than ty, or ty* if pointer is set, e.g. float* for a Vector<i32> or float for
+/// an i32 scalar
+///
+/// Programs are built with -cl-kernel-arg-info, the check is skipped if the implementation
+/// still does not report parameter types.
+fn check_arg_type(kernel: &ocl::Kernel, idx: u32, ty: &str, pointer: bool) -> Result<()> {
+    use ocl::enums::{ KernelArgInfo, KernelArgInfoResult };
+
+    if let KernelArgInfoResult::TypeName(name) = kernel.arg_info(idx, KernelArgInfo::TypeName) {
+        let name: String = name.trim_end_matches('\0').chars().filter(|c| !c.is_whitespace()).collect();
+        let expected = if pointer { format!("{}*", ty) } else { ty.to_owned() };
+        if !name.is_empty() && name != expected {
+            return Err(Error::InvalidArgument(format!(
+                "{} {} bound to parameter {} of type {}", ty, if pointer { "buffer" } else { "scalar" }, idx, name)));
+        }
+    }
+    Ok(())
+}
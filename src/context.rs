@@ -0,0 +1,630 @@
+extern crate ocl;
+
+use std::sync::{ Arc, LazyLock, Mutex, MutexGuard };
+use std::collections::HashMap;
+
+use traits::Parameter;
+use error::{ Error, Result };
+use expr::fused_src;
+use profile::{ Profiler, ProfileReport };
+use pool::BufferPool;
+use device::{ self, DeviceProperties, DeviceSelector };
+use call::KernelCall;
+
+use OclData;
+use FusedProgram;
+use FUSED_PROGRAM_LIMIT;
+use Kernels;
+use KernelsGuard;
+use KERNEL_SRC;
+use PROFILING;
+use build_program;
+use cache;
+use check_type_supported;
+use supports_fp64;
+use FP64_PRAGMA;
+use get_cpu;
+use get_gpu;
+use instantiate_kernels;
+use setup_type;
+
+/// Handle to the device that Vectors and Matrices are stored and computed on
+///
+/// Every context has its own OpenCL queue and kernels so independent contexts never block
+/// each other. Cloning a context is cheap, the clone refers to the same device and queue.
+/// Vectors and Matrices from different contexts can not be used together in one operation.
+/// Operations from several threads on one context run concurrently since every operation sets
+/// the arguments of kernel instances of its own.
+///
+/// Extra queues of a context are created with new_stream(), see there.
+#[derive(Clone)]
+pub struct Context {
+    inner: Arc<Backend>,
+
+    /// Queue commands of this handle are enqueued on, None for the native backend
+    queue: Option<ocl::Queue>,
+}
+
+/// The large fields are boxed to keep the native backend small
+enum Backend {
+    OpenCl {
+        platform: ocl::Platform,
+        data: Box<Mutex<OclData>>,
+
+        /// Queues of all streams, the first one being the queue of data
+        streams: Mutex<Vec<ocl::Queue>>,
+
+        /// Only set if profiling was enabled when the context was created
+        profiler: Option<Mutex<Profiler>>,
+
+        /// Buffers of dropped vectors, separate from data since vectors may be dropped while
+        /// data is locked
+        pool: Box<Mutex<BufferPool>>,
+    },
+    Native,
+}
+
+/// Default context or the message of the error that occurred when creating it
+static DEFAULT_CONTEXT: LazyLock<::std::result::Result<Context, String>> = LazyLock::new(|| {
+    if cfg!(feature = "no_opencl") {
+        Ok(Context::native())
+    } else {
+        match DeviceSelector::from_env() {
+            Ok(ref selector) if !selector.is_empty() =>
+                Context::try_select(selector).map_err(|e| e.to_string()),
+            Ok(_) => match get_gpu().or_else(get_cpu) {
+                Some((platform, device)) =>
+                    Context::try_new(platform, device).map_err(|e| e.to_string()),
+                None => Ok(Context::native()),
+            },
+            Err(e) => Err(format!("Invalid device selection: {}", e)),
+        }
+    }
+});
+
+impl Context {
+    /// Create context for the specified OpenCL platform and device
+    ///
+    /// Kernels are compiled separately for every element type the first time the type is used
+    /// in this context.
+    pub fn new(platform: ocl::Platform, device: ocl::Device) -> Context {
+        Context::try_new(platform, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as new() but returns an error instead of panicking if the OpenCL context or queue
+    /// could not be created
+    pub fn try_new(platform: ocl::Platform, device: ocl::Device) -> Result<Context> {
+        let context = ocl::Context::builder()
+            .platform(platform)
+            .devices(device)
+            .build()?;
+        let profiling = *PROFILING.lock().unwrap();
+        let properties = if profiling { Some(ocl::flags::QUEUE_PROFILING_ENABLE) } else { None };
+        let queue = ocl::Queue::new(&context, device, properties)?;
+
+        Ok(Context::with_queue(platform, context, device, queue, profiling))
+    }
+
+    /// Create context running on an existing OpenCL queue, e.g. to share buffers with other
+    /// OpenCL code through Vector::from_buffer() and into_buffer()
+    ///
+    /// Kernels and transfers are enqueued on queue. Profiling is enabled if queue was created
+    /// with QUEUE_PROFILING_ENABLE. Fails for out-of-order queues, vectors rely on the order of
+    /// the commands of their queue.
+    pub fn from_queue(queue: ocl::Queue) -> Result<Context> {
+        use ocl::enums::{ CommandQueueInfo, CommandQueueInfoResult, DeviceInfo, DeviceInfoResult };
+
+        let device = queue.device();
+        let platform = match device.info(DeviceInfo::Platform) {
+            DeviceInfoResult::Platform(id) => ocl::Platform::new(id),
+            DeviceInfoResult::Error(e) => return Err(Error::OpenCl(*e)),
+            _ => return Err(Error::OpenCl("Unexpected device info result".into())),
+        };
+        let properties = match queue.info(CommandQueueInfo::Properties) {
+            CommandQueueInfoResult::Properties(p) => p,
+            CommandQueueInfoResult::Error(e) => return Err(Error::OpenCl(*e)),
+            _ => return Err(Error::OpenCl("Unexpected command queue info result".into())),
+        };
+        if properties.contains(ocl::flags::QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE) {
+            return Err(Error::InvalidArgument("Out-of-order queues are not supported".to_owned()));
+        }
+        let profiling = properties.contains(ocl::flags::QUEUE_PROFILING_ENABLE);
+
+        Ok(Context::with_queue(platform, queue.context(), device, queue, profiling))
+    }
+
+    /// Same as from_queue() but uses the queue of pro_que
+    pub fn from_pro_que(pro_que: &ocl::ProQue) -> Result<Context> {
+        Context::from_queue(pro_que.queue().clone())
+    }
+
+    fn with_queue(platform: ocl::Platform, context: ocl::Context, device: ocl::Device, queue: ocl::Queue, profiling: bool) -> Context {
+        Context {
+            queue: Some(queue.clone()),
+            inner: Arc::new(Backend::OpenCl {
+                platform,
+                streams: Mutex::new(vec![queue.clone()]),
+                profiler: if profiling { Some(Mutex::new(Profiler::default())) } else { None },
+                pool: Box::new(Mutex::new(BufferPool::default())),
+                data: Box::new(Mutex::new(OclData {
+                    context,
+                    device,
+                    queue,
+                    kernels: HashMap::new(),
+                    idle: HashMap::new(),
+                    kernel_src: KERNEL_SRC.lock().unwrap().clone(),
+                    fused: HashMap::new(),
+                    fused_uses: 0,
+                })),
+            })
+        }
+    }
+
+    /// Create context for the first device matching selector
+    ///
+    /// Fails with Error::NoDevice if no device matches.
+    pub fn try_select(selector: &DeviceSelector) -> Result<Context> {
+        let devices = device::list();
+        match selector.select(&devices) {
+            Some(d) => Context::try_new(d.platform, d.device),
+            None => Err(Error::NoDevice(format!("No OpenCL device matches {}", selector))),
+        }
+    }
+
+    /// Same as default() but returns an error instead of panicking if the default context
+    /// could not be created
+    pub fn try_default() -> Result<Context> {
+        match *DEFAULT_CONTEXT {
+            Ok(ref context) => Ok(context.clone()),
+            Err(ref e) => Err(Error::NoDevice(e.clone())),
+        }
+    }
+
+    /// Create context using the pure Rust backend which runs on the host
+    pub fn native() -> Context {
+        Context {
+            inner: Arc::new(Backend::Native),
+            queue: None,
+        }
+    }
+
+    /// Create a new stream of this context
+    ///
+    /// The stream is a handle to the same device, kernels and buffer pool with its own OpenCL
+    /// queue, so commands of different streams may run concurrently, e.g. an upload on one stream
+    /// overlaps a kernel on another. Vectors created in the stream enqueue their operations there,
+    /// an operation runs on the stream of its first operand. Vectors of different streams of a
+    /// context can be used together since operations wait for the events of all operands.
+    ///
+    /// For the native backend the stream is the same as self.
+    pub fn new_stream(&self) -> Result<Context> {
+        let (data, streams) = match *self.inner {
+            Backend::OpenCl { ref data, ref streams, .. } => (data, streams),
+            Backend::Native => return Ok(self.clone()),
+        };
+
+        let properties = if self.is_profiling() { Some(ocl::flags::QUEUE_PROFILING_ENABLE) } else { None };
+        let queue = {
+            let data = data.lock().unwrap();
+            ocl::Queue::new(&data.context, data.device, properties)?
+        };
+        streams.lock().unwrap().push(queue.clone());
+
+        Ok(Context {
+            inner: self.inner.clone(),
+            queue: Some(queue),
+        })
+    }
+
+    /// Returns true if self and other are the same stream of the same context, see new_stream()
+    pub fn is_same_stream(&self, other: &Context) -> bool {
+        *self == *other && match (&self.queue, &other.queue) {
+            (Some(a), Some(b)) => a.as_ptr() == b.as_ptr(),
+            _ => true,
+        }
+    }
+
+    /// Block until all commands enqueued on this stream have completed
+    pub fn finish(&self) -> Result<()> {
+        match self.queue {
+            Some(ref queue) => Ok(queue.finish()?),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns true if this context uses the pure Rust backend instead of OpenCL
+    pub fn is_native(&self) -> bool {
+        match *self.inner {
+            Backend::Native => true,
+            Backend::OpenCl { .. } => false,
+        }
+    }
+
+    /// Properties of the OpenCL device of context, None if the context uses the native backend
+    pub fn device(&self) -> Option<DeviceProperties> {
+        match (&*self.inner, &self.queue) {
+            (&Backend::OpenCl { platform, .. }, Some(queue)) => Some(DeviceProperties::query(platform, queue.device())),
+            _ => None,
+        }
+    }
+
+    /// OpenCL queue of context, or of the stream for contexts created with new_stream(), None if
+    /// the context uses the native backend
+    pub fn queue(&self) -> Option<&ocl::Queue> {
+        self.queue.as_ref()
+    }
+
+    /// Returns true if f64 Vectors and Matrices can be used in this context
+    ///
+    /// The native backend always supports f64, OpenCL devices need cl_khr_fp64 or cl_amd_fp64.
+    /// Operations on f64 fail with Error::Unsupported on devices without it.
+    pub fn supports_f64(&self) -> bool {
+        match self.queue {
+            Some(ref queue) => supports_fp64(queue.device()),
+            None => true,
+        }
+    }
+
+    /// Returns true if kernel and transfer times are recorded in this context, see set_profiling()
+    pub fn is_profiling(&self) -> bool {
+        self.profiler().is_some()
+    }
+
+    /// Timing statistics of all kernels and transfers run in this context so far, None if
+    /// profiling was not enabled when the context was created
+    ///
+    /// Waits for all commands that have not completed yet.
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.profiler().map(|p| p.lock().unwrap().report())
+    }
+
+    fn profiler(&self) -> Option<&Mutex<Profiler>> {
+        match *self.inner {
+            Backend::OpenCl { ref profiler, .. } => profiler.as_ref(),
+            Backend::Native => None,
+        }
+    }
+
+    /// Record the time of the kernel command of event if profiling is enabled
+    pub(crate) fn profile_kernel(&self, kernel: &ocl::Kernel, event: &ocl::Event) {
+        if let Some(profiler) = self.profiler() {
+            profiler.lock().unwrap().record(&kernel.name(), None, event);
+        }
+    }
+
+    /// Record the time of the transfer command of event if profiling is enabled
+    pub(crate) fn profile_transfer<T: Parameter>(&self, name: &str, event: &ocl::Event) {
+        if let Some(profiler) = self.profiler() {
+            profiler.lock().unwrap().record(name, Some(T::type_to_str()), event);
+        }
+    }
+
+    /// Set the limit in bytes of the buffers kept for reuse, 256 MiB by default
+    ///
+    /// Buffers of dropped vectors are kept and reused for new vectors of the same type and
+    /// length. Buffers that would exceed the limit are released instead. If the buffers kept
+    /// already exceed the new limit, all of them are released.
+    pub fn set_pool_limit(&self, bytes: usize) {
+        if let Some(pool) = self.pool() {
+            pool.lock().unwrap().set_limit(bytes);
+        }
+    }
+
+    /// Release all buffers kept for reuse
+    pub fn trim_pool(&self) {
+        if let Some(pool) = self.pool() {
+            pool.lock().unwrap().trim();
+        }
+    }
+
+    /// Bytes of the buffers currently kept for reuse, always 0 for the native backend
+    pub fn pool_size(&self) -> usize {
+        self.pool().map_or(0, |pool| pool.lock().unwrap().size())
+    }
+
+    fn pool(&self) -> Option<&Mutex<BufferPool>> {
+        match *self.inner {
+            Backend::OpenCl { ref pool, .. } => Some(&**pool),
+            Backend::Native => None,
+        }
+    }
+
+    /// Take a buffer of len elements from the pool, internal use only
+    ///
+    /// Commands using the buffer have to wait for the returned events.
+    pub(crate) fn take_buffer<T: Parameter>(&self, len: usize) -> Option<(ocl::Buffer<T>, ocl::EventList)> {
+        self.pool().and_then(|pool| pool.lock().unwrap().take(len))
+    }
+
+    /// Record that buffer was allocated for a vector of this context and may be reused once the
+    /// vector is dropped, internal use only
+    pub(crate) fn own_buffer<T: Parameter>(&self, buffer: &ocl::Buffer<T>) {
+        if let Some(pool) = self.pool() {
+            pool.lock().unwrap().own(buffer);
+        }
+    }
+
+    /// Never reuse buffer since other code may keep a clone of it, internal use only
+    pub(crate) fn disown_buffer<T: Parameter>(&self, buffer: &ocl::Buffer<T>) {
+        if let Some(pool) = self.pool() {
+            pool.lock().unwrap().disown(buffer);
+        }
+    }
+
+    /// Return the buffer of a dropped vector to the pool, internal use only
+    ///
+    /// events are those of the commands still writing or reading the buffer, see
+    /// Vector::write_wait_list(), commands of the next vector using it wait for them.
+    pub(crate) fn recycle_buffer<T: Parameter>(&self, buffer: ocl::Buffer<T>, events: ocl::EventList) {
+        let pool = match *self.inner {
+            Backend::OpenCl { ref pool, .. } => pool,
+            Backend::Native => return,
+        };
+
+        // Sub-buffers of views share the memory of their vector
+        if buffer.is_sub_buffer() {
+            return;
+        }
+
+        pool.lock().unwrap().put(buffer, events);
+    }
+
+    /// Alignment of the origin of sub-buffers in bytes, see Vector::view()
+    pub(crate) fn sub_buffer_align(&self) -> Result<usize> {
+        use ocl::enums::{ DeviceInfo, DeviceInfoResult };
+
+        let device = self.lock_data()?.device;
+        match device.info(DeviceInfo::MemBaseAddrAlign) {
+            DeviceInfoResult::MemBaseAddrAlign(bits) => Ok(::std::cmp::max(bits as usize / 8, 1)),
+            DeviceInfoResult::Error(e) => Err(Error::OpenCl(*e)),
+            _ => Ok(1),
+        }
+    }
+
+    /// OpenCL context of context, None if the context uses the native backend
+    pub fn cl_context(&self) -> Option<ocl::Context> {
+        self.lock_data().ok().map(|data| data.context.clone())
+    }
+
+    /// OpenCL queue of context, fails if the context uses the native backend
+    pub(crate) fn cl_queue(&self) -> Result<&ocl::Queue> {
+        self.queue().ok_or_else(requires_opencl)
+    }
+
+    fn lock_data<'a>(&'a self) -> Result<MutexGuard<'a, OclData>> {
+        match *self.inner {
+            Backend::OpenCl { ref data, .. } => Ok(data.lock().unwrap()),
+            Backend::Native => Err(requires_opencl()),
+        }
+    }
+
+    /// Get all OpenCL data with kernels for type T set up, internal use only
+    ///
+    /// Fails with Error::Build if the kernels for T do not compile on the device. The build log
+    /// is kept so the type is not compiled again on every call.
+    pub(crate) fn cl_data<'a, T: Parameter>(&'a self) -> Result<MutexGuard<'a, OclData>> {
+        let mut data = self.lock_data()?;
+        let ty = T::type_to_str();
+
+        if !data.kernels.contains_key(ty) {
+            let kernels = match setup_type::<T>(&data) {
+                Ok(kernels) => Ok(kernels),
+                Err(Error::Build(log)) => Err(log),
+                Err(e) => return Err(e),
+            };
+            data.kernels.insert(ty.to_owned(), kernels);
+        }
+
+        if let Err(ref log) = data.kernels[ty] {
+            return Err(Error::Build(log.clone()));
+        }
+        Ok(data)
+    }
+
+    /// Lend an instance of the kernels for type T, internal use only
+    ///
+    /// The context is only locked while taking the instance, a new instance is created if all
+    /// are in use by other operations.
+    pub(crate) fn kernels<'a, T: Parameter>(&'a self) -> Result<KernelsGuard<'a>> {
+        let mut data = self.cl_data::<T>()?;
+        let ty = T::type_to_str();
+
+        let kernels = match data.idle.get_mut(ty).and_then(|idle| idle.pop()) {
+            Some(kernels) => kernels,
+            None => {
+                let program = data.kernels[ty].as_ref().unwrap().program.clone();
+                instantiate_kernels::<T>(&data, program)?
+            },
+        };
+
+        Ok(KernelsGuard { context: self, ty, kernels: Some(kernels) })
+    }
+
+    /// Keep kernels lent by kernels() for later operations
+    ///
+    /// Kernels keep the buffers bound to their arguments alive, these are replaced by
+    /// placeholders first. Instances failing to do so are released instead.
+    pub(crate) fn return_kernels(&self, ty: &str, mut kernels: Kernels) {
+        if kernels.unbind_buffers().is_err() {
+            return;
+        }
+        if let Ok(mut data) = self.lock_data() {
+            data.idle.entry(ty.to_owned()).or_insert_with(Vec::new).push(kernels);
+        }
+    }
+
+    /// Run f with the fused kernel computing expr, the program is compiled on first use and
+    /// cached by type, argument counts and expression. The kernel has no declared arguments, f
+    /// has to set all of them by index.
+    ///
+    /// At most FUSED_PROGRAM_LIMIT programs are cached, compiling another one releases the least
+    /// recently used program.
+    ///
+    /// Like kernels(), f gets a kernel instance of its own and runs without the context locked.
+    pub(crate) fn with_fused_kernel<T, R, F>(&self, expr: &str, input_count: usize, scalar_count: usize, f: F) -> Result<R>
+        where T: Parameter, F: FnOnce(&mut ocl::Kernel) -> Result<R>
+    {
+        let ty = T::type_to_str();
+        let key = format!("{}:{}:{}:{}", ty, input_count, scalar_count, expr);
+
+        let mut kernel = {
+            let mut data = self.lock_data()?;
+
+            if !data.fused.contains_key(&key) {
+                check_type_supported(ty, data.device)?;
+
+                let mut src = fused_src(expr, input_count, scalar_count).replace("{T}", ty);
+                if ty == "double" {
+                    src = FP64_PRAGMA.to_owned() + &src;
+                }
+
+                let program = cache::build_program(&data.context, data.device, &src)
+                    .map_err(|e| Error::Build(e.to_string()))?;
+                // Large enough for every element type
+                let placeholder = ocl::Buffer::<u8>::builder().queue(data.queue.clone()).dims(8).build()?;
+
+                if data.fused.len() >= FUSED_PROGRAM_LIMIT {
+                    // Instances lent out right now are dropped when they are returned
+                    let oldest = data.fused.iter().min_by_key(|&(_, p)| p.last_use).map(|(k, _)| k.clone());
+                    if let Some(oldest) = oldest {
+                        data.fused.remove(&oldest);
+                    }
+                }
+                data.fused.insert(key.clone(), FusedProgram { program, idle: Vec::new(), placeholder, last_use: 0 });
+            }
+
+            data.fused_uses += 1;
+            let (queue, uses) = (data.queue.clone(), data.fused_uses);
+            let fused = data.fused.get_mut(&key).unwrap();
+            fused.last_use = uses;
+            match fused.idle.pop() {
+                Some(kernel) => kernel,
+                // Arguments are set by index since their number depends on the expression
+                None => ocl::Kernel::new(format!("{}_fused", ty), &fused.program)?.queue(queue),
+            }
+        };
+
+        let res = f(&mut kernel);
+
+        // Like return_kernels(), idle instances must not keep the buffers of the call alive. An
+        // instance f failed with is dropped.
+        let mut data = self.lock_data()?;
+        if let Some(fused) = data.fused.get_mut(&key) {
+            if res.is_ok() && unbind_fused(&mut kernel, input_count, &fused.placeholder).is_ok() {
+                fused.idle.push(kernel);
+            }
+        }
+        res
+    }
+
+    /// Compile extra kernel source for this context, see ::create_kernel() for how to write it
+    ///
+    /// The source is compiled right away for every type already used in this context and on
+    /// first use for all other types. Fails with Error::Build containing the build log if the
+    /// source does not compile for a type already in use, the source is not added then.
+    pub fn add_kernel_src(&self, src: &str) -> Result<()> {
+        let mut data = self.lock_data()?;
+
+        let mut programs = Vec::new();
+        for (ty, kernels) in data.kernels.iter() {
+            if kernels.is_ok() {
+                programs.push((ty.clone(), build_program(&data, ty, src)?));
+            }
+        }
+
+        for (ty, program) in programs {
+            if let Ok(ref mut kernels) = *data.kernels.get_mut(&ty).unwrap() {
+                kernels.programs.push(program);
+            }
+        }
+        data.kernel_src.push(src.to_owned());
+        Ok(())
+    }
+
+    /// Create Kernel object from kernel source registered with ::add_kernel_src() or
+    /// add_kernel_src(), see ::create_kernel()
+    ///
+    /// The kernel is enqueued on the stream of this context by default.
+    pub fn create_kernel<T: Parameter>(&self, kernel_name: &str) -> ocl::Kernel {
+        match self.try_create_kernel::<T>(kernel_name) {
+            Ok(kernel) => kernel,
+            Err(error) => panic!("Failed to create kernel. Forgot to register kernel source?: {}", error)
+        }
+    }
+
+    /// Call the kernel {T}_kernel_name from registered source with typed arguments, see
+    /// call::KernelCall
+    ///
+    /// The kernel runs on the stream of this context.
+    pub fn kernel_call<'a, T: Parameter>(&self, kernel_name: &str) -> KernelCall<'a, T> {
+        KernelCall::new(self, kernel_name)
+    }
+
+    /// Same as create_kernel() but returns an error instead of panicking
+    ///
+    /// Only source compiled for T is searched. If the kernel is not found and some registered
+    /// source failed to compile for T, the error is Error::Build with the build log.
+    pub fn try_create_kernel<T: Parameter>(&self, kernel_name: &str) -> Result<ocl::Kernel> {
+        let data = self.cl_data::<T>()?;
+        let kernels = data.kernels[T::type_to_str()].as_ref().unwrap();
+
+        let queue = self.cl_queue()?;
+
+        for program in kernels.programs.iter().rev() {
+            if let Ok(kernel) = ocl::Kernel::new(kernel_name, program) {
+                return Ok(kernel.queue(queue.clone()));
+            }
+        }
+
+        match ocl::Kernel::new(kernel_name, &kernels.program) {
+            Ok(kernel) => Ok(kernel.queue(queue.clone())),
+            Err(_) if !kernels.build_errors.is_empty() => Err(Error::Build(kernels.build_errors.join("\n"))),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Fails if other is not the same context as self
+    pub(crate) fn check_same(&self, other: &Context) -> Result<()> {
+        if self == other {
+            Ok(())
+        } else {
+            Err(Error::ContextMismatch)
+        }
+    }
+}
+
+pub(crate) fn requires_opencl() -> Error {
+    Error::NoDevice("Operation requires OpenCL but context uses the native backend".to_owned())
+}
+
+impl PartialEq for Context {
+    fn eq(&self, other: &Context) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Default for Context {
+    /// Get the default context, which is used by all constructors not taking a context
+    ///
+    /// The default context uses the first GPU found, or any other OpenCL device if there is
+    /// no GPU. If there are no OpenCL devices at all, or if the `no_opencl` feature is enabled,
+    /// the native backend is used.
+    ///
+    /// The device can be chosen at runtime through environment variables or a config file, see
+    /// DeviceSelector::from_env(). Creating the default context fails if no device matches.
+    fn default() -> Context {
+        Context::try_default().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Bind placeholder to the buffer arguments C, A0, A1... of a fused kernel, see fused_src()
+fn unbind_fused(kernel: &mut ocl::Kernel, input_count: usize, placeholder: &ocl::Buffer<u8>) -> ocl::Result<()> {
+    use ocl::enums::KernelArg;
+
+    for idx in 0..=input_count as u32 {
+        unsafe {
+            kernel.set_arg_unchecked(idx, KernelArg::Mem::<u8>(placeholder.core()))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,290 @@
+//! Listing of OpenCL platforms and devices and their capabilities
+//!
+//! ```ignore
+//! for d in device::list() {
+//!     println!("{}", d);
+//! }
+//! ```
+
+extern crate ocl;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use error::{ Error, Result };
+use platforms;
+use supports_fp64;
+
+/// Type of an OpenCL device
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeviceKind {
+    Gpu,
+    Cpu,
+    Accelerator,
+    Other,
+}
+
+impl DeviceKind {
+    fn from_type(ty: ocl::DeviceType) -> DeviceKind {
+        if ty.contains(ocl::flags::DEVICE_TYPE_GPU) {
+            DeviceKind::Gpu
+        } else if ty.contains(ocl::flags::DEVICE_TYPE_CPU) {
+            DeviceKind::Cpu
+        } else if ty.contains(ocl::flags::DEVICE_TYPE_ACCELERATOR) {
+            DeviceKind::Accelerator
+        } else {
+            DeviceKind::Other
+        }
+    }
+}
+
+impl fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            DeviceKind::Gpu => "GPU",
+            DeviceKind::Cpu => "CPU",
+            DeviceKind::Accelerator => "Accelerator",
+            DeviceKind::Other => "Other",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Capabilities of an OpenCL device
+#[derive(Clone, Debug)]
+pub struct DeviceProperties {
+    pub platform: ocl::Platform,
+    pub device: ocl::Device,
+
+    pub platform_name: String,
+    pub name: String,
+    pub vendor: String,
+    pub kind: DeviceKind,
+
+    pub compute_units: u32,
+    /// Bytes of global memory
+    pub global_mem_size: u64,
+    /// Bytes of local memory per work group
+    pub local_mem_size: u64,
+    pub max_work_group_size: usize,
+    /// Alignment of sub-buffer origins in bits, views of vectors have to start at a multiple
+    pub mem_base_addr_align: u32,
+
+    /// Double precision through cl_khr_fp64 or cl_amd_fp64, required for f64
+    pub fp64: bool,
+    /// Half precision through cl_khr_fp16
+    pub fp16: bool,
+
+    /// OpenCL version supported by the device, e.g. 1.2
+    pub version: String,
+}
+
+impl DeviceProperties {
+    /// Query the properties of device on platform
+    pub fn query(platform: ocl::Platform, device: ocl::Device) -> DeviceProperties {
+        use ocl::enums::{ DeviceInfo, DeviceInfoResult };
+
+        let kind = match device.info(DeviceInfo::Type) {
+            DeviceInfoResult::Type(ty) => DeviceKind::from_type(ty),
+            _ => DeviceKind::Other,
+        };
+        let compute_units = match device.info(DeviceInfo::MaxComputeUnits) {
+            DeviceInfoResult::MaxComputeUnits(n) => n,
+            _ => 0,
+        };
+        let global_mem_size = match device.info(DeviceInfo::GlobalMemSize) {
+            DeviceInfoResult::GlobalMemSize(n) => n,
+            _ => 0,
+        };
+        let local_mem_size = match device.info(DeviceInfo::LocalMemSize) {
+            DeviceInfoResult::LocalMemSize(n) => n,
+            _ => 0,
+        };
+        let max_work_group_size = match device.info(DeviceInfo::MaxWorkGroupSize) {
+            DeviceInfoResult::MaxWorkGroupSize(n) => n,
+            _ => 0,
+        };
+        let mem_base_addr_align = match device.info(DeviceInfo::MemBaseAddrAlign) {
+            DeviceInfoResult::MemBaseAddrAlign(n) => n,
+            _ => 0,
+        };
+        let extensions = device.info(DeviceInfo::Extensions).to_string();
+
+        DeviceProperties {
+            platform,
+            device,
+
+            platform_name: platform.name(),
+            name: device.name(),
+            vendor: device.vendor(),
+            kind,
+
+            compute_units,
+            global_mem_size,
+            local_mem_size,
+            max_work_group_size,
+            mem_base_addr_align,
+
+            fp64: supports_fp64(device),
+            fp16: extensions.contains("cl_khr_fp16"),
+
+            version: device.info(DeviceInfo::Version).to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DeviceProperties {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {}): {} compute units, {} MiB global memory, {} KiB local memory, \
+                   max work group size {}, OpenCL {}",
+            self.name, self.kind, self.platform_name, self.compute_units,
+            self.global_mem_size / (1024 * 1024), self.local_mem_size / 1024,
+            self.max_work_group_size, self.version)?;
+        if self.fp64 {
+            write!(f, ", fp64")?;
+        }
+        if self.fp16 {
+            write!(f, ", fp16")?;
+        }
+        Ok(())
+    }
+}
+
+/// List every device of every OpenCL platform, empty if there are none
+pub fn list() -> Vec<DeviceProperties> {
+    let mut res = Vec::new();
+    for platform in platforms() {
+        if let Ok(devices) = ocl::Device::list_all(platform) {
+            for device in devices {
+                res.push(DeviceProperties::query(platform, device));
+            }
+        }
+    }
+    res
+}
+
+/// Criteria for picking a device, every criterion that is set has to match
+///
+/// Used for the default context, see from_env().
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceSelector {
+    /// Substring of the platform name, case insensitive
+    pub platform: Option<String>,
+    /// Substring of the device name, case insensitive
+    pub device: Option<String>,
+    pub kind: Option<DeviceKind>,
+    /// Index among the devices matching the other criteria
+    pub index: Option<usize>,
+}
+
+impl DeviceSelector {
+    /// Selector for the default context
+    ///
+    /// Read from the config file named by LINEAR_ALGEBRA_CONFIG, or linear_algebra.conf in the
+    /// current directory if that exists. The environment variables LINEAR_ALGEBRA_PLATFORM,
+    /// LINEAR_ALGEBRA_DEVICE, LINEAR_ALGEBRA_DEVICE_TYPE and LINEAR_ALGEBRA_DEVICE_INDEX
+    /// override the values of the file. See parse() for the file format.
+    pub fn from_env() -> Result<DeviceSelector> {
+        use std::env;
+        use std::fs;
+
+        let path = env::var_os("LINEAR_ALGEBRA_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from("linear_algebra.conf")).filter(|p| p.is_file()));
+
+        let mut selector = match path {
+            Some(path) => DeviceSelector::parse(&fs::read_to_string(&path)?)?,
+            None => DeviceSelector::default(),
+        };
+
+        for &(var, key) in &[
+            ("LINEAR_ALGEBRA_PLATFORM", "platform"),
+            ("LINEAR_ALGEBRA_DEVICE", "device"),
+            ("LINEAR_ALGEBRA_DEVICE_TYPE", "type"),
+            ("LINEAR_ALGEBRA_DEVICE_INDEX", "index"),
+        ] {
+            if let Ok(value) = env::var(var) {
+                selector.set(key, &value)?;
+            }
+        }
+
+        Ok(selector)
+    }
+
+    /// Parse a config file with one `key = value` per line, keys are platform, device, type
+    /// (gpu, cpu, accelerator or other) and index. Lines starting with # are ignored.
+    ///
+    /// ```text
+    /// # Use the second GPU of the NVIDIA platform
+    /// platform = nvidia
+    /// type = gpu
+    /// index = 1
+    /// ```
+    pub fn parse(content: &str) -> Result<DeviceSelector> {
+        let mut selector = DeviceSelector::default();
+        for line in content.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => selector.set(key.trim(), value.trim())?,
+                _ => return Err(Error::Format(format!("Expected key = value in device config: {}", line))),
+            }
+        }
+        Ok(selector)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "platform" => self.platform = Some(value.to_owned()),
+            "device" => self.device = Some(value.to_owned()),
+            "type" => self.kind = Some(match value.to_lowercase().as_str() {
+                "gpu" => DeviceKind::Gpu,
+                "cpu" => DeviceKind::Cpu,
+                "accelerator" => DeviceKind::Accelerator,
+                "other" => DeviceKind::Other,
+                _ => return Err(Error::Format(format!("Unknown device type: {}", value))),
+            }),
+            "index" => self.index = Some(value.parse().map_err(|_|
+                Error::Format(format!("Invalid device index: {}", value)))?),
+            _ => return Err(Error::Format(format!("Unknown device config key: {}", key))),
+        }
+        Ok(())
+    }
+
+    /// Returns true if no criterion is set
+    pub fn is_empty(&self) -> bool {
+        *self == DeviceSelector::default()
+    }
+
+    /// Returns true if d matches all criteria except index
+    pub fn matches(&self, d: &DeviceProperties) -> bool {
+        let contains = |haystack: &str, needle: &Option<String>| match *needle {
+            Some(ref needle) => haystack.to_lowercase().contains(&needle.to_lowercase()),
+            None => true,
+        };
+
+        contains(&d.platform_name, &self.platform)
+            && contains(&d.name, &self.device)
+            && self.kind.map_or(true, |kind| kind == d.kind)
+    }
+
+    /// Pick the device from devices, None if no device matches
+    pub fn select<'a>(&self, devices: &'a [DeviceProperties]) -> Option<&'a DeviceProperties> {
+        devices.iter()
+            .filter(|d| self.matches(d))
+            .nth(self.index.unwrap_or(0))
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "platform: {}, device: {}, type: {}, index: {}",
+            self.platform.as_ref().map_or("any", |s| s.as_str()),
+            self.device.as_ref().map_or("any", |s| s.as_str()),
+            self.kind.map_or("any".to_owned(), |k| k.to_string()),
+            self.index.unwrap_or(0))
+    }
+}
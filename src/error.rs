@@ -0,0 +1,105 @@
+extern crate ocl;
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+/// Errors returned by the fallible try_ functions in this crate
+#[derive(Debug)]
+pub enum Error {
+    /// Dimensions of the operands do not match
+    ShapeMismatch(String),
+
+    /// Operands belong to different contexts
+    ContextMismatch,
+
+    /// Error reported by OpenCL
+    OpenCl(ocl::Error),
+
+    /// Kernel source failed to compile, contains the build log
+    Build(String),
+
+    /// Error when reading from or writing to a file
+    Io(io::Error),
+
+    /// File content is not in the expected format
+    Format(String),
+
+    /// No device is available for the requested operation
+    NoDevice(String),
+
+    /// Element type is not supported by the device, e.g. f64 without fp64 support
+    Unsupported(String),
+
+    /// Argument is outside the values accepted by the function
+    InvalidArgument(String),
+
+    /// Expression string of map() or zip_with() could not be parsed
+    Expression(String),
+}
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn shape_mismatch(a: usize, b: usize) -> Error {
+        Error::ShapeMismatch(format!("{} elements vs {} elements", a, b))
+    }
+
+    pub(crate) fn mat_shape_mismatch(a: (usize, usize), b: (usize, usize)) -> Error {
+        Error::ShapeMismatch(format!("{}x{} matrix vs {}x{} matrix", a.0, a.1, b.0, b.1))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::ShapeMismatch(ref s) => write!(f, "Shape mismatch: {}", s),
+            Error::ContextMismatch => write!(f, "Can not mix Vectors or Matrices from different contexts"),
+            Error::OpenCl(ref e) => write!(f, "OpenCL error: {}", e),
+            Error::Build(ref log) => write!(f, "Kernel source failed to compile: {}", log),
+            Error::Io(ref e) => write!(f, "IO error: {}", e),
+            Error::Format(ref s) => write!(f, "Invalid file format: {}", s),
+            Error::NoDevice(ref s) => write!(f, "No device available: {}", s),
+            Error::Unsupported(ref s) => write!(f, "Unsupported element type: {}", s),
+            Error::InvalidArgument(ref s) => write!(f, "Invalid argument: {}", s),
+            Error::Expression(ref s) => write!(f, "Invalid expression: {}", s),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::ShapeMismatch(_) => "shape mismatch",
+            Error::ContextMismatch => "context mismatch",
+            Error::OpenCl(_) => "OpenCL error",
+            Error::Build(_) => "kernel source failed to compile",
+            Error::Io(_) => "IO error",
+            Error::Format(_) => "invalid file format",
+            Error::NoDevice(_) => "no device available",
+            Error::Unsupported(_) => "unsupported element type",
+            Error::InvalidArgument(_) => "invalid argument",
+            Error::Expression(_) => "invalid expression",
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            Error::OpenCl(ref e) => Some(e),
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ocl::Error> for Error {
+    fn from(e: ocl::Error) -> Error {
+        Error::OpenCl(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
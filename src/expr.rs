@@ -0,0 +1,756 @@
+extern crate ocl;
+
+use ::std::ops::{ Add, Sub, Mul, Div };
+
+use traits::Parameter;
+use vector::*;
+use matrix::Matrix;
+use view::{ VectorView, VectorViewMut };
+use error::{ Error, Result };
+use context::Context;
+use native;
+
+/// Lazy elementwise expression over vectors
+///
+/// Returned by the arithmetic operators on vector references and combined with other
+/// expressions, vectors and scalars using the same operators. Nothing is computed until eval()
+/// is called, which runs the whole expression as one fused kernel. Every input vector is read
+/// only once and no intermediate buffers are allocated.
+///
+/// ```ignore
+/// let c = &(&a + &b) * 2.0;      // Nothing computed yet
+/// let c: Vector<f32> = c.eval();  // One kernel computing (a[i] + b[i]) * 2.0
+/// ```
+#[derive(Clone)]
+pub struct Expr<'a, T: Parameter> {
+    node: Node<'a, T>,
+}
+
+/// Lazy elementwise expression over matrices of the same shape, see Expr
+///
+/// Returned by + and - between matrix references and by * and / with a scalar, matrix
+/// products are computed right away.
+#[derive(Clone)]
+pub struct MatrixExpr<'a, T: Parameter> {
+    expr: Expr<'a, T>,
+    /// Shapes of all input matrices, checked when evaluating
+    shapes: Vec<(usize, usize)>,
+}
+
+#[derive(Clone)]
+enum Node<'a, T: Parameter> {
+    Vector(&'a Vector<T>),
+    Scalar(T),
+    Binary(BinOp, Box<Node<'a, T>>, Box<Node<'a, T>>),
+}
+
+#[derive(Copy, Clone)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinOp {
+    fn symbol(&self) -> &'static str {
+        match *self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+        }
+    }
+}
+
+/// Inputs of an expression in the order they are passed to the fused kernel
+struct Args<'a, T: Parameter> {
+    vectors: Vec<&'a Vector<T>>,
+    scalars: Vec<T>,
+}
+
+impl<'a, T: Parameter> Node<'a, T> {
+    /// Build OpenCL source for the node, vectors are named A0, A1... and scalars S0, S1...
+    ///
+    /// The same vector used several times is passed to the kernel only once.
+    fn to_src(&self, args: &mut Args<'a, T>) -> String {
+        match *self {
+            Node::Vector(v) => {
+                let idx = match args.vectors.iter().position(|x| ::std::ptr::eq(*x, v)) {
+                    Some(idx) => idx,
+                    None => {
+                        args.vectors.push(v);
+                        args.vectors.len() - 1
+                    }
+                };
+                format!("A{}[i]", idx)
+            },
+            Node::Scalar(s) => {
+                args.scalars.push(s);
+                format!("S{}", args.scalars.len() - 1)
+            },
+            Node::Binary(op, ref a, ref b) =>
+                format!("({} {} {})", a.to_src(args), op.symbol(), b.to_src(args)),
+        }
+    }
+
+    /// Evaluate the node on the host, used by the native backend
+    fn eval_host(&self, len: usize) -> Vec<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        match *self {
+            Node::Vector(v) => v.to_vec(),
+            Node::Scalar(s) => vec![s; len],
+            Node::Binary(op, ref a, ref b) => {
+                let (a, b) = (a.eval_host(len), b.eval_host(len));
+                match op {
+                    BinOp::Add => native::zip(&a, &b, |a, b| a + b),
+                    BinOp::Sub => native::zip(&a, &b, |a, b| a - b),
+                    BinOp::Mul => native::zip(&a, &b, |a, b| a * b),
+                    BinOp::Div => native::zip(&a, &b, |a, b| a / b),
+                }
+            },
+        }
+    }
+}
+
+impl<'a, T: Parameter> Expr<'a, T> {
+    pub(crate) fn from_vector(v: &'a Vector<T>) -> Expr<'a, T> {
+        Expr { node: Node::Vector(v) }
+    }
+
+    fn binary(op: BinOp, a: Node<'a, T>, b: Node<'a, T>) -> Expr<'a, T> {
+        Expr { node: Node::Binary(op, Box::new(a), Box::new(b)) }
+    }
+
+    /// Compute the expression, panics on error, see try_eval()
+    pub fn eval(self) -> Vector<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        self.try_eval().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Compute the expression in one fused kernel
+    ///
+    /// Fails if the vectors have different lengths or belong to different contexts.
+    pub fn try_eval(self) -> Result<Vector<T>>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        let mut args = Args { vectors: Vec::new(), scalars: Vec::new() };
+        let expr = self.node.to_src(&mut args);
+
+        let len = check_inputs(&args.vectors)?;
+        let context = args.vectors[0].context.clone();
+        if context.is_native() {
+            return Ok(Vector::from_host(self.node.eval_host(len), &context));
+        }
+
+        run_fused(&context, len, len, &args, &expr)
+    }
+
+    /// OpenCL C source of the expression, inputs are named as in the fused kernel
+    fn src(&self) -> String {
+        self.node.to_src(&mut Args { vectors: Vec::new(), scalars: Vec::new() })
+    }
+}
+
+impl<'a, T: Parameter> MatrixExpr<'a, T> {
+    pub(crate) fn from_matrix(m: &'a Matrix<T>) -> MatrixExpr<'a, T> {
+        MatrixExpr {
+            expr: Expr::from_vector(&m.data),
+            shapes: vec![(m.get_row_count(), m.get_col_count())],
+        }
+    }
+
+    fn binary(op: BinOp, a: MatrixExpr<'a, T>, b: MatrixExpr<'a, T>) -> MatrixExpr<'a, T> {
+        let mut shapes = a.shapes;
+        shapes.extend(b.shapes);
+        MatrixExpr { expr: Expr::binary(op, a.expr.node, b.expr.node), shapes }
+    }
+
+    fn scalar(op: BinOp, a: MatrixExpr<'a, T>, scalar: T) -> MatrixExpr<'a, T> {
+        MatrixExpr { expr: Expr::binary(op, a.expr.node, Node::Scalar(scalar)), shapes: a.shapes }
+    }
+
+    /// Compute the expression, panics on error, see try_eval()
+    pub fn eval(self) -> Matrix<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        self.try_eval().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Compute the expression in one fused kernel
+    ///
+    /// Fails if the matrices have different shapes or belong to different contexts.
+    pub fn try_eval(self) -> Result<Matrix<T>>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        let (row_count, col_count) = self.shapes[0];
+        if let Some(shape) = self.shapes.iter().find(|s| **s != self.shapes[0]) {
+            return Err(Error::mat_shape_mismatch(self.shapes[0], *shape));
+        }
+        Ok(Matrix::from_data(self.expr.try_eval()?, row_count, col_count))
+    }
+}
+
+/// Fails if the vectors have different lengths or belong to different contexts, returns their
+/// length otherwise
+fn check_inputs<T: Parameter>(vectors: &[&Vector<T>]) -> Result<usize> {
+    let first = vectors[0];
+    for v in &vectors[1..] {
+        first.context.check_same(&v.context)?;
+        if v.len() != first.len() {
+            return Err(Error::shape_mismatch(first.len(), v.len()));
+        }
+    }
+    Ok(first.len())
+}
+
+/// Run the fused kernel computing expr on args for len elements of rows with col_count
+/// elements, the result is in the stream of context
+fn run_fused<T: Parameter>(context: &Context, len: usize, col_count: usize, args: &Args<T>, expr: &str) -> Result<Vector<T>> {
+    let mut res = unsafe { Vector::try_uninitialized_in(len, context)? };
+    if len == 0 {
+        return Ok(res);
+    }
+
+    context.with_fused_kernel::<T, _, _>(expr, args.vectors.len(), args.scalars.len(), |kernel| {
+        use ocl::enums::KernelArg;
+
+        unsafe {
+            // Arguments in the order of fused_src(): C, A0, A1... S0, S1... col_count
+            kernel.set_arg_unchecked(0, KernelArg::Mem::<T>(res.buf().core()))?;
+            for (idx, v) in args.vectors.iter().enumerate() {
+                kernel.set_arg_unchecked(1 + idx as u32, KernelArg::Mem::<T>(v.buf().core()))?;
+            }
+            let scalar_offset = 1 + args.vectors.len() as u32;
+            for (idx, s) in args.scalars.iter().enumerate() {
+                kernel.set_arg_unchecked(scalar_offset + idx as u32, KernelArg::Scalar(*s))?;
+            }
+            let col_count_idx = scalar_offset + args.scalars.len() as u32;
+            kernel.set_arg_unchecked(col_count_idx, KernelArg::Scalar(col_count as u64))?;
+
+            let mut event = ocl::Event::empty();
+            let wait_list = Vector::wait_list(&args.vectors, &res);
+            kernel.cmd().queue(context.cl_queue()?).ewait(&wait_list).enew(&mut event).gws(len).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&args.vectors, &event);
+            res.set_event(event);
+        }
+        Ok(())
+    })?;
+
+    Ok(res)
+}
+
+/// Names of the inputs of expression strings in Vector::zip_n(), i is the element index
+pub const INPUT_NAMES: [&str; 8] = ["a", "b", "c", "d", "e", "f", "g", "h"];
+
+/// Compute the OpenCL C expression expr for every element, see Vector::map()
+///
+/// names are the names of inputs in expr, i is the element index. On OpenCL devices expr is
+/// compiled into a fused kernel cached like the ones of Expr. The native backend evaluates
+/// expressions made of + - * /, parentheses, numbers, inputs and i only.
+pub(crate) fn eval_str<T>(inputs: &[&Vector<T>], names: &[&str], expr: &str) -> Result<Vector<T>>
+    where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    let len = check_inputs(inputs)?;
+    let context = inputs[0].context.clone();
+    eval_tokens(&context, len, None, inputs, names, &tokenize(expr)?)
+}
+
+/// Compute expr for every element of a row_count x col_count matrix in row major order, r and
+/// c are the row and column index, see Matrix::from_index_expr()
+pub(crate) fn eval_index_str<T>(context: &Context, row_count: usize, col_count: usize, expr: &str) -> Result<Vector<T>>
+    where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    let tokens = tokenize(expr)?;
+    eval_tokens(context, row_count * col_count, Some(col_count), &[], &[], &tokens)
+}
+
+/// Compute tokens for len elements, col_count is set if r and c can be used
+fn eval_tokens<T>(context: &Context, len: usize, col_count: Option<usize>, inputs: &[&Vector<T>], names: &[&str],
+    tokens: &[Token]) -> Result<Vector<T>>
+    where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    // OpenCL computes x * 1.5 in floating point and truncates the result while the native
+    // backend would convert 1.5 to T first, so only integer literals are allowed for integers
+    if T::from_f64(0.5) == T::ZERO {
+        for token in tokens {
+            if let Token::Number(ref n) = *token {
+                if is_float_literal(n) {
+                    return Err(Error::Expression(format!(
+                        "floating point literal {} in an expression of {} elements", n, T::type_to_str())));
+                }
+            }
+        }
+    }
+
+    if context.is_native() {
+        let ast = Parser { tokens, pos: 0, names, indices: col_count.is_some() }.parse()?;
+        let data: Vec<Vec<T>> = inputs.iter().map(|v| v.to_vec()).collect();
+        let cols = col_count.unwrap_or(len);
+        let res = (0..len).map(|idx| ast.eval(&data, idx, cols)).collect();
+        return Ok(Vector::from_host(res, context));
+    }
+
+    let mut src = String::new();
+    for token in tokens {
+        match *token {
+            Token::Ident(ref id) => match names.iter().position(|n| n == id) {
+                Some(idx) => src += &format!("A{}[i]", idx),
+                // Indices are converted to T like on the host, e.g. r / c divides as float for f32
+                None if id == "i" => src += "(({T})i)",
+                None if col_count.is_some() && id == "r" => src += "(({T})(i / col_count))",
+                None if col_count.is_some() && id == "c" => src += "(({T})(i % col_count))",
+                None => src += id,
+            },
+            Token::Number(ref n) => src += n,
+            Token::Op(c) => src.push(c),
+        }
+        src.push(' ');
+    }
+
+    let args = Args { vectors: inputs.to_vec(), scalars: Vec::new() };
+    run_fused(context, len, col_count.unwrap_or(len), &args, &format!("({})", src.trim_end()))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Op(char),
+}
+
+/// Split expr into tokens, fails on characters that can not be part of an expression
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let start = pos;
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            tokens.push(Token::Ident(chars[start..pos].iter().collect()));
+        } else if c.is_ascii_digit() || c == '.' {
+            // Also covers exponents and suffixes like 1e-3f
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '.'
+                || ((chars[pos] == '-' || chars[pos] == '+') && (chars[pos - 1] == 'e' || chars[pos - 1] == 'E')))
+            {
+                pos += 1;
+            }
+            tokens.push(Token::Number(chars[start..pos].iter().collect()));
+        } else if "+-*/%()<>=!&|^~?:,".contains(c) {
+            tokens.push(Token::Op(c));
+            pos += 1;
+        } else {
+            return Err(Error::Expression(format!("unexpected '{}' in {}", c, expr)));
+        }
+    }
+
+    if tokens.is_empty() {
+        return Err(Error::Expression("empty expression".to_owned()));
+    }
+    Ok(tokens)
+}
+
+/// Whether the number literal n has a fraction or exponent, e.g. 1.5, 2e3 or 1.0f
+fn is_float_literal(n: &str) -> bool {
+    let n = n.to_lowercase();
+    !n.starts_with("0x") && n.contains(&['.', 'e', 'f'][..])
+}
+
+/// Expression evaluated by the native backend
+enum HostExpr {
+    Input(usize),
+    Index,
+    Row,
+    Col,
+    Literal(f64),
+    Neg(Box<HostExpr>),
+    Binary(BinOp, Box<HostExpr>, Box<HostExpr>),
+}
+
+impl HostExpr {
+    fn eval<T>(&self, inputs: &[Vec<T>], idx: usize, col_count: usize) -> T
+        where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        match *self {
+            HostExpr::Input(n) => inputs[n][idx],
+            HostExpr::Index => T::from_f64(idx as f64),
+            HostExpr::Row => T::from_f64((idx / col_count) as f64),
+            HostExpr::Col => T::from_f64((idx % col_count) as f64),
+            HostExpr::Literal(v) => T::from_f64(v),
+            HostExpr::Neg(ref a) => T::ZERO - a.eval(inputs, idx, col_count),
+            HostExpr::Binary(op, ref a, ref b) => {
+                let (a, b) = (a.eval(inputs, idx, col_count), b.eval(inputs, idx, col_count));
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div => a / b,
+                }
+            },
+        }
+    }
+}
+
+/// Recursive descent parser for the expressions supported by the native backend
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    names: &'a [&'a str],
+    /// r and c are the row and column index
+    indices: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(&mut self) -> Result<HostExpr> {
+        let expr = self.sum()?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some(t) => Err(self.unsupported(t)),
+        }
+    }
+
+    fn sum(&mut self) -> Result<HostExpr> {
+        let mut a = self.product()?;
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(&Token::Op('+')) => BinOp::Add,
+                Some(&Token::Op('-')) => BinOp::Sub,
+                _ => return Ok(a),
+            };
+            self.pos += 1;
+            a = HostExpr::Binary(op, Box::new(a), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<HostExpr> {
+        let mut a = self.unary()?;
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(&Token::Op('*')) => BinOp::Mul,
+                Some(&Token::Op('/')) => BinOp::Div,
+                _ => return Ok(a),
+            };
+            self.pos += 1;
+            a = HostExpr::Binary(op, Box::new(a), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<HostExpr> {
+        let token = match self.tokens.get(self.pos) {
+            Some(t) => t,
+            None => return Err(Error::Expression("unexpected end of expression".to_owned())),
+        };
+        self.pos += 1;
+
+        match *token {
+            Token::Op('-') => Ok(HostExpr::Neg(Box::new(self.unary()?))),
+            Token::Op('+') => self.unary(),
+            Token::Op('(') => {
+                let expr = self.sum()?;
+                match self.tokens.get(self.pos) {
+                    Some(&Token::Op(')')) => {
+                        self.pos += 1;
+                        Ok(expr)
+                    },
+                    _ => Err(Error::Expression("missing )".to_owned())),
+                }
+            },
+            Token::Number(ref n) => n.trim_end_matches(&['f', 'F'][..]).parse()
+                .map(HostExpr::Literal)
+                .map_err(|_| Error::Expression(format!("invalid number {}", n))),
+            Token::Ident(ref id) if id == "i" => Ok(HostExpr::Index),
+            Token::Ident(ref id) => match self.names.iter().position(|n| n == id) {
+                Some(idx) => Ok(HostExpr::Input(idx)),
+                None if self.indices && id == "r" => Ok(HostExpr::Row),
+                None if self.indices && id == "c" => Ok(HostExpr::Col),
+                None => Err(self.unsupported(token)),
+            },
+            _ => Err(self.unsupported(token)),
+        }
+    }
+
+    /// Tokens the native backend can not evaluate, e.g. function calls, may still be valid
+    /// OpenCL C
+    fn unsupported(&self, token: &Token) -> Error {
+        let text = match *token {
+            Token::Ident(ref s) | Token::Number(ref s) => s.clone(),
+            Token::Op(c) => c.to_string(),
+        };
+        Error::Expression(format!("The native backend can not evaluate '{}' in expressions", text))
+    }
+}
+
+/// OpenCL source of the fused kernel `{T}_fused` computing expr, uses the same `{T}` template
+/// scheme as kernels.cl
+///
+/// col_count is the row length of the result, used for the row and column index of
+/// Matrix::from_index_expr().
+pub(crate) fn fused_src(expr: &str, input_count: usize, scalar_count: usize) -> String {
+    let mut params = String::from("global {T}* C");
+    for idx in 0..input_count {
+        params += &format!(", global {{T}}* A{}", idx);
+    }
+    for idx in 0..scalar_count {
+        params += &format!(", {{T}} S{}", idx);
+    }
+    params += ", ulong col_count";
+
+    format!(
+        "#define i get_global_id(0)\n\
+         kernel void {{T}}_fused({}) {{\n\
+         \tC[i] = {};\n\
+         }}\n",
+        params, expr
+    )
+}
+
+/// Parts of an expression, used to implement the operators for every combination
+trait IntoNode<'a, T: Parameter> {
+    fn into_node(self) -> Node<'a, T>;
+}
+
+impl<'a, T: Parameter> IntoNode<'a, T> for Expr<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        self.node
+    }
+}
+
+impl<'a, T: Parameter> IntoNode<'a, T> for &Expr<'a, T> {
+    fn into_node(self) -> Node<'a, T> {
+        self.node.clone()
+    }
+}
+
+impl<'a, T: Parameter> IntoNode<'a, T> for &'a Vector<T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::Vector(self)
+    }
+}
+
+impl<'a, 'v, T: Parameter> IntoNode<'a, T> for &'a VectorView<'v, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::Vector(self)
+    }
+}
+
+impl<'a, 'v, T: Parameter> IntoNode<'a, T> for &'a VectorViewMut<'v, T> {
+    fn into_node(self) -> Node<'a, T> {
+        Node::Vector(self)
+    }
+}
+
+/// Matrix parts of an expression
+trait IntoMatrixExpr<'a, T: Parameter> {
+    fn into_matrix_expr(self) -> MatrixExpr<'a, T>;
+}
+
+impl<'a, T: Parameter> IntoMatrixExpr<'a, T> for MatrixExpr<'a, T> {
+    fn into_matrix_expr(self) -> MatrixExpr<'a, T> {
+        self
+    }
+}
+
+impl<'a, T: Parameter> IntoMatrixExpr<'a, T> for &MatrixExpr<'a, T> {
+    fn into_matrix_expr(self) -> MatrixExpr<'a, T> {
+        self.clone()
+    }
+}
+
+impl<'a, T: Parameter> IntoMatrixExpr<'a, T> for &'a Matrix<T> {
+    fn into_matrix_expr(self) -> MatrixExpr<'a, T> {
+        MatrixExpr::from_matrix(self)
+    }
+}
+
+macro_rules! impl_expr_op {
+    ($tr:ident, $f:ident, $op:expr, [$($lt:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<'a, $($lt)* T> $tr<$rhs> for $lhs
+            where T: Parameter + $tr<T, Output=T>
+        {
+            type Output = Expr<'a, T>;
+            fn $f(self, other: $rhs) -> Expr<'a, T> {
+                Expr::binary($op, self.into_node(), other.into_node())
+            }
+        }
+    };
+    ($tr:ident, $f:ident, $op:expr, [$($lt:tt)*] $lhs:ty) => {
+        impl<'a, $($lt)* T> $tr<T> for $lhs
+            where T: Parameter + $tr<T, Output=T>
+        {
+            type Output = Expr<'a, T>;
+            fn $f(self, scalar: T) -> Expr<'a, T> {
+                Expr::binary($op, self.into_node(), Node::Scalar(scalar))
+            }
+        }
+    };
+}
+
+/// Operators between expressions, vectors and scalars
+macro_rules! impl_expr_ops {
+    ($( [$($lt:tt)*] $lhs:ty $(, $rhs:ty)*; )+) => { $(
+        impl_expr_op!(Add, add, BinOp::Add, [$($lt)*] $lhs $(, $rhs)*);
+        impl_expr_op!(Sub, sub, BinOp::Sub, [$($lt)*] $lhs $(, $rhs)*);
+        impl_expr_op!(Mul, mul, BinOp::Mul, [$($lt)*] $lhs $(, $rhs)*);
+        impl_expr_op!(Div, div, BinOp::Div, [$($lt)*] $lhs $(, $rhs)*);
+    )+ }
+}
+
+impl_expr_ops! {
+    [] Expr<'a, T>, Expr<'a, T>;
+    ['b,] Expr<'a, T>, &'b Expr<'a, T>;
+    [] Expr<'a, T>, &'a Vector<T>;
+    [] Expr<'a, T>;
+    ['b,] &'b Expr<'a, T>, Expr<'a, T>;
+    ['b, 'c,] &'b Expr<'a, T>, &'c Expr<'a, T>;
+    ['b,] &'b Expr<'a, T>, &'a Vector<T>;
+    ['b,] &'b Expr<'a, T>;
+    [] &'a Vector<T>, Expr<'a, T>;
+    ['b,] &'a Vector<T>, &'b Expr<'a, T>;
+    [] &'a Vector<T>, &'a Vector<T>;
+    [] &'a Vector<T>;
+}
+
+// Operators with views, which read their range of the viewed vector like a vector
+impl_expr_ops! {
+    ['v,] Expr<'a, T>, &'a VectorView<'v, T>;
+    ['v,] Expr<'a, T>, &'a VectorViewMut<'v, T>;
+    ['b, 'v,] &'b Expr<'a, T>, &'a VectorView<'v, T>;
+    ['b, 'v,] &'b Expr<'a, T>, &'a VectorViewMut<'v, T>;
+    ['v,] &'a Vector<T>, &'a VectorView<'v, T>;
+    ['v,] &'a Vector<T>, &'a VectorViewMut<'v, T>;
+    ['v,] &'a VectorView<'v, T>, Expr<'a, T>;
+    ['b, 'v,] &'a VectorView<'v, T>, &'b Expr<'a, T>;
+    ['v,] &'a VectorView<'v, T>, &'a Vector<T>;
+    ['v, 'w,] &'a VectorView<'v, T>, &'a VectorView<'w, T>;
+    ['v, 'w,] &'a VectorView<'v, T>, &'a VectorViewMut<'w, T>;
+    ['v,] &'a VectorView<'v, T>;
+    ['v,] &'a VectorViewMut<'v, T>, Expr<'a, T>;
+    ['b, 'v,] &'a VectorViewMut<'v, T>, &'b Expr<'a, T>;
+    ['v,] &'a VectorViewMut<'v, T>, &'a Vector<T>;
+    ['v, 'w,] &'a VectorViewMut<'v, T>, &'a VectorView<'w, T>;
+    ['v, 'w,] &'a VectorViewMut<'v, T>, &'a VectorViewMut<'w, T>;
+    ['v,] &'a VectorViewMut<'v, T>;
+}
+
+macro_rules! impl_matrix_expr_op {
+    ($tr:ident, $f:ident, $op:expr, [$($lt:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<'a, $($lt)* T> $tr<$rhs> for $lhs
+            where T: Parameter + $tr<T, Output=T>
+        {
+            type Output = MatrixExpr<'a, T>;
+            fn $f(self, other: $rhs) -> MatrixExpr<'a, T> {
+                MatrixExpr::binary($op, self.into_matrix_expr(), other.into_matrix_expr())
+            }
+        }
+    };
+    ($tr:ident, $f:ident, $op:expr, [$($lt:tt)*] $lhs:ty) => {
+        impl<'a, $($lt)* T> $tr<T> for $lhs
+            where T: Parameter + $tr<T, Output=T>
+        {
+            type Output = MatrixExpr<'a, T>;
+            fn $f(self, scalar: T) -> MatrixExpr<'a, T> {
+                MatrixExpr::scalar($op, self.into_matrix_expr(), scalar)
+            }
+        }
+    };
+}
+
+/// Sums and differences of matrices and expressions, products and quotients with scalars
+macro_rules! impl_matrix_expr_ops {
+    ($( [$($lt:tt)*] $lhs:ty, $rhs:ty; )+) => { $(
+        impl_matrix_expr_op!(Add, add, BinOp::Add, [$($lt)*] $lhs, $rhs);
+        impl_matrix_expr_op!(Sub, sub, BinOp::Sub, [$($lt)*] $lhs, $rhs);
+    )+ };
+    ($( [$($lt:tt)*] $lhs:ty; )+) => { $(
+        impl_matrix_expr_op!(Mul, mul, BinOp::Mul, [$($lt)*] $lhs);
+        impl_matrix_expr_op!(Div, div, BinOp::Div, [$($lt)*] $lhs);
+    )+ };
+}
+
+impl_matrix_expr_ops! {
+    [] MatrixExpr<'a, T>, MatrixExpr<'a, T>;
+    ['b,] MatrixExpr<'a, T>, &'b MatrixExpr<'a, T>;
+    [] MatrixExpr<'a, T>, &'a Matrix<T>;
+    ['b,] &'b MatrixExpr<'a, T>, MatrixExpr<'a, T>;
+    ['b, 'c,] &'b MatrixExpr<'a, T>, &'c MatrixExpr<'a, T>;
+    ['b,] &'b MatrixExpr<'a, T>, &'a Matrix<T>;
+    [] &'a Matrix<T>, MatrixExpr<'a, T>;
+    ['b,] &'a Matrix<T>, &'b MatrixExpr<'a, T>;
+    [] &'a Matrix<T>, &'a Matrix<T>;
+}
+
+impl_matrix_expr_ops! {
+    [] MatrixExpr<'a, T>;
+    ['b,] &'b MatrixExpr<'a, T>;
+    [] &'a Matrix<T>;
+}
+
+impl<'a, T> From<Expr<'a, T>> for Vector<T>
+    where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn from(expr: Expr<'a, T>) -> Vector<T> {
+        expr.eval()
+    }
+}
+
+impl<'a, T> From<MatrixExpr<'a, T>> for Matrix<T>
+    where T: Parameter + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn from(expr: MatrixExpr<'a, T>) -> Matrix<T> {
+        expr.eval()
+    }
+}
+
+impl<'a, T> PartialEq<Vector<T>> for Expr<'a, T>
+    where T: Parameter + PartialEq + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn eq(&self, other: &Vector<T>) -> bool {
+        self.clone().eval() == *other
+    }
+}
+
+impl<'a, T> PartialEq<Expr<'a, T>> for Vector<T>
+    where T: Parameter + PartialEq + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn eq(&self, other: &Expr<'a, T>) -> bool {
+        other == self
+    }
+}
+
+impl<'a, T> PartialEq<Matrix<T>> for MatrixExpr<'a, T>
+    where T: Parameter + PartialEq + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn eq(&self, other: &Matrix<T>) -> bool {
+        self.clone().eval() == *other
+    }
+}
+
+impl<'a, T> PartialEq<MatrixExpr<'a, T>> for Matrix<T>
+    where T: Parameter + PartialEq + Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+{
+    fn eq(&self, other: &MatrixExpr<'a, T>) -> bool {
+        other == self
+    }
+}
+
+impl<'a, T: Parameter> ::std::fmt::Debug for Expr<'a, T> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Expr({})", self.src())
+    }
+}
+
+impl<'a, T: Parameter> ::std::fmt::Debug for MatrixExpr<'a, T> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "MatrixExpr({})", self.expr.src())
+    }
+}
@@ -1,8 +1,7 @@
 
-/// The content of this file will only be used when running tests for this
-/// this crate. Users of this library should create ther own file with the
-/// same name in their src/ folder where thay may define extra OpenCL
-/// functions read doc for create_kernel() for more info
+/// Extra kernel source used by the tests of this crate. Users of this library
+/// register their own source at runtime with add_kernel_src() or
+/// Context::add_kernel_src(), read doc for create_kernel() for more info
 
 #if defined(IS_FLOAT) || defined(IS_DOUBLE)
 
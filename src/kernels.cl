@@ -169,6 +169,11 @@ kernel void {T}_mul_mat_mat(global {T}* C, global {T}* A, global {T}* B, int C_c
 }
 
 
+kernel void {T}_set_diagonal(global {T}* C, int col_count, {T} value) {
+	C[i * col_count + i] = value;
+}
+
+
 //Iterates over one row per work item
 #define row get_global_id(0)
 kernel void {T}_mul_mat_mat_row(global {T}* C, global {T}* A, global {T}* B, int C_col_count, int A_col_count) {
@@ -191,4 +196,84 @@ kernel void {T}_mul_mat_mat_col(global {T}* C, global {T}* A, global {T}* B, int
 		C[row * C_col_count + col] = {T}_dot_product(&A[A_col_count * row], 1, &B[col], C_col_count, A_col_count);
 	}
 }
-#undef col
\ No newline at end of file
+#undef col
+
+//---------------------------------------------------------------------------------------------------------------------
+//--------------------------------------------------- Random ----------------------------------------------------------
+//---------------------------------------------------------------------------------------------------------------------
+
+//Integer, uniform and bernoulli results have to match the host versions in random.rs bit for bit,
+//normal results only up to the precision of log, sqrt and cos
+#pragma OPENCL FP_CONTRACT OFF
+
+//Philox4x32-10 block of the element at index
+uint4 {T}_philox(ulong index, ulong stream, ulong seed) {
+	uint4 ctr = (uint4)((uint)index, (uint)(index >> 32), (uint)stream, (uint)(stream >> 32));
+	uint2 key = (uint2)((uint)seed, (uint)(seed >> 32));
+
+	for (int round = 0; round < 10; round++) {
+		uint hi0 = mul_hi(0xD2511F53u, ctr.x);
+		uint lo0 = 0xD2511F53u * ctr.x;
+		uint hi1 = mul_hi(0xCD9E8D57u, ctr.z);
+		uint lo1 = 0xCD9E8D57u * ctr.z;
+		ctr = (uint4)(hi1 ^ ctr.y ^ key.x, lo1, hi0 ^ ctr.w ^ key.y, lo0);
+		key += (uint2)(0x9E3779B9u, 0xBB67AE85u);
+	}
+	return ctr;
+}
+
+kernel void {T}_rng_uniform(global {T}* C, ulong seed, ulong stream, {T} low, {T} high, ulong range) {
+	uint4 bits = {T}_philox(i, stream, seed);
+#if defined(IS_FLOAT)
+	C[i] = low + (float)(bits.x >> 8) * (1.0f / 16777216.0f) * (high - low);
+#elif defined(IS_DOUBLE)
+	ulong r = ((ulong)bits.x << 32) | bits.y;
+	C[i] = low + (double)(r >> 11) * (1.0 / 9007199254740992.0) * (high - low);
+#else
+	ulong r = ((ulong)bits.x << 32) | bits.y;
+	C[i] = ({T})((ulong)low + mul_hi(r, range));
+#endif
+}
+
+//Box-Muller, only used for float and double
+kernel void {T}_rng_normal(global {T}* C, ulong seed, ulong stream, {T} mean, {T} std_dev) {
+	uint4 bits = {T}_philox(i, stream, seed);
+#if defined(IS_FLOAT)
+	float u1 = (float)((bits.x >> 8) + 1) * (1.0f / 16777216.0f);
+	float u2 = (float)(bits.y >> 8) * (1.0f / 16777216.0f);
+	C[i] = mean + std_dev * (sqrt(-2.0f * log(u1)) * cos(2.0f * M_PI_F * u2));
+#elif defined(IS_DOUBLE)
+	ulong r1 = ((ulong)bits.x << 32) | bits.y;
+	ulong r2 = ((ulong)bits.z << 32) | bits.w;
+	double u1 = (double)((r1 >> 11) + 1) * (1.0 / 9007199254740992.0);
+	double u2 = (double)(r2 >> 11) * (1.0 / 9007199254740992.0);
+	C[i] = mean + std_dev * (sqrt(-2.0 * log(u1)) * cos(2.0 * M_PI * u2));
+#endif
+}
+
+kernel void {T}_rng_bernoulli(global {T}* C, ulong seed, ulong stream, ulong threshold) {
+	uint4 bits = {T}_philox(i, stream, seed);
+	C[i] = (ulong)bits.x < threshold ? ({T})(1) : ({T})(0);
+}
+
+
+//---------------------------------------------------------------------------------------------------------------------
+//-------------------------------------------------- Generators -------------------------------------------------------
+//---------------------------------------------------------------------------------------------------------------------
+
+kernel void {T}_arange(global {T}* C, {T} start, {T} step) {
+	C[i] = start + ({T})i * step;
+}
+
+//The last element is exactly stop, only used for float and double
+kernel void {T}_linspace(global {T}* C, {T} start, {T} step, {T} stop, ulong last) {
+#if defined(IS_FLOAT) || defined(IS_DOUBLE)
+	C[i] = i == last ? stop : start + ({T})i * step;
+#endif
+}
+
+kernel void {T}_logspace(global {T}* C, {T} start, {T} step, {T} stop, ulong last, {T} base) {
+#if defined(IS_FLOAT) || defined(IS_DOUBLE)
+	C[i] = pow(base, i == last ? stop : start + ({T})i * step);
+#endif
+}
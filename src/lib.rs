@@ -1,8 +1,5 @@
 extern crate ocl;
 
-#[macro_use]
-extern crate lazy_static;
-
 use ocl::ProQue;
 use ocl::Buffer;
 
@@ -10,23 +7,73 @@ pub mod vector;
 pub mod matrix;
 pub mod traits;
 pub mod util;
+pub mod context;
+pub mod error;
+pub mod expr;
+pub mod profile;
+pub mod device;
+pub mod transfer;
+pub mod call;
+pub mod random;
+pub mod view;
+mod native;
+mod cache;
+mod tune;
+mod pool;
 
 #[cfg(test)]
 mod tests;
 
 use traits::Parameter;
-use std::sync::Mutex;
+use context::Context;
+use error::{ Error, Result };
+use std::sync::{ LazyLock, Mutex };
 use std::collections::HashMap;
-use std::sync::MutexGuard;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 struct OclData {
-    queue: ocl::ProQue,
-    kernels: HashMap<String, Kernels>
+    context: ocl::Context,
+    device: ocl::Device,
+    queue: ocl::Queue,
+
+    /// Kernels by type, compiled the first time the type is used. Holds the build log instead if
+    /// the type failed to compile, which does not affect other types.
+    kernels: HashMap<String, std::result::Result<Kernels, String>>,
+
+    /// Instances of the kernels above not lent to an operation right now by type, see
+    /// Context::kernels()
+    idle: HashMap<String, Vec<Kernels>>,
+
+    /// Kernel source registered by the user, compiled for every type on first use
+    kernel_src: Vec<String>,
+
+    /// Programs of fused expression kernels by type and expression, see expr::Expr
+    fused: HashMap<String, FusedProgram>,
+
+    /// Number of fused kernel launches, used to find the least recently used program
+    fused_uses: u64,
 }
 
-#[derive(Copy, Clone)]
+/// Program of a fused expression kernel, see Context::with_fused_kernel()
+struct FusedProgram {
+    program: ocl::Program,
+
+    /// Kernel instances not lent to an operation right now
+    idle: Vec<ocl::Kernel>,
+
+    /// Bound to the buffer arguments of idle instances
+    placeholder: Buffer<u8>,
+
+    /// OclData::fused_uses when the program was last used
+    last_use: u64,
+}
+
+/// Fused programs kept per context, the least recently used one is released for a new one
+const FUSED_PROGRAM_LIMIT: usize = 256;
+
+/// Launch parameters of a kernel
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct KernelParams {
     pub work_group_size: usize,
     pub work_group_count: usize,
@@ -62,188 +109,351 @@ pub struct Kernels {
     //Matrix
 
     mul_mat_mat: ocl::Kernel,
+    set_diagonal: ocl::Kernel,
 
-    queue: ocl::Queue,
+    //Random
+
+    rng_uniform: ocl::Kernel,
+    rng_normal: ocl::Kernel,
+    rng_bernoulli: ocl::Kernel,
+
+    //Generators
+
+    arange: ocl::Kernel,
+    linspace: ocl::Kernel,
+    logspace: ocl::Kernel,
+
+    /// Program the kernels above are created from
+    program: ocl::Program,
+
+    /// Programs built from kernel source registered by the user, newest last
+    programs: Vec<ocl::Program>,
+
+    /// Build logs of registered kernel source that failed to compile
+    build_errors: Vec<String>,
+
+    /// One element Buffer<T> and Buffer<u8> bound to the buffer arguments of idle kernels, which
+    /// would keep the buffers of dropped vectors alive otherwise
+    placeholders: Box<dyn std::any::Any + Send>,
+
+    /// unbind_buffers() for the type of the kernels
+    unbind: fn(&mut Kernels) -> ocl::Result<()>,
+}
+
+impl Kernels {
+    /// Bind placeholders to all buffer arguments, see Context::return_kernels()
+    fn unbind_buffers(&mut self) -> ocl::Result<()> {
+        (self.unbind)(self)
+    }
+}
+
+/// Bind the placeholders of kernels of type T to all of their buffer arguments
+fn unbind_buffers<T: Parameter>(kernels: &mut Kernels) -> ocl::Result<()> {
+    let (ref buf, ref bytes) = *kernels.placeholders.downcast_ref::<(Buffer<T>, Buffer<u8>)>().unwrap();
+
+    macro_rules! unbind {
+        ($( $kernel:ident: $($name:expr),+; )+) => { $( $(
+            kernels.$kernel.set_arg_buf_named($name, Some(buf))?;
+        )+ )+ }
+    }
+
+    unbind! {
+        add_vec_vec: "C", "A", "B";
+        sub_vec_vec: "C", "A", "B";
+        mul_vec_vec: "C", "A", "B";
+        div_vec_vec: "C", "A", "B";
+        dot_vec_vec: "a", "b", "results";
+        add_assign_vec_vec: "C", "B";
+        sub_assign_vec_vec: "C", "B";
+        mul_assign_vec_vec: "C", "B";
+        div_assign_vec_vec: "C", "B";
+        mul_vec_scl: "C", "A";
+        div_vec_scl: "C", "A";
+        mul_assign_vec_scl: "C";
+        div_assign_vec_scl: "C";
+        eq_vec: "A", "B";
+        sum_vec: "data", "results";
+        mul_vec_mat: "C", "A", "B";
+        mul_vec_transpose_mat: "C", "A", "B";
+        mul_mat_mat: "C", "A", "B";
+        set_diagonal: "C";
+        rng_uniform: "C";
+        rng_normal: "C";
+        rng_bernoulli: "C";
+        arange: "C";
+        linspace: "C";
+        logspace: "C";
+    }
+    kernels.eq_vec.set_arg_buf_named("C", Some(bytes))?;
+    Ok(())
 }
 
-pub struct KernelsGuard<'a, 'b>(MutexGuard<'a, OclData>, &'b str);
+/// Kernels lent to one operation, returned to the idle instances of the context when dropped
+///
+/// Every operation sets the arguments of its own kernel instances, so operations on several
+/// threads do not race each other and only lock the context to take and return instances.
+pub struct KernelsGuard<'a> {
+    context: &'a Context,
+    ty: &'static str,
+    kernels: Option<Kernels>,
+}
 
-impl<'a, 'b> Deref for KernelsGuard<'a, 'b> {
+impl<'a> Deref for KernelsGuard<'a> {
     type Target = Kernels;
     fn deref(&self) -> &Self::Target {
-        self.0.kernels.get(self.1).unwrap()
+        self.kernels.as_ref().unwrap()
     }
 }
 
-impl<'a, 'b> DerefMut for KernelsGuard<'a, 'b> {
+impl<'a> DerefMut for KernelsGuard<'a> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.kernels.get_mut(self.1).unwrap()
+        self.kernels.as_mut().unwrap()
     }
 }
 
-lazy_static! {
-    pub static ref TYPES: Mutex<Vec<&'static str>> = Mutex::new(vec![
-        "uchar",    "char",
-        "ushort",   "short",
-        "uint",     "int",
-        "ulong",    "long",
-        "float"
-    ]);
+impl<'a> Drop for KernelsGuard<'a> {
+    fn drop(&mut self) {
+        if let Some(kernels) = self.kernels.take() {
+            self.context.return_kernels(self.ty, kernels);
+        }
+    }
+}
+
+/// Kernel source registered with add_kernel_src()
+static KERNEL_SRC: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Whether new contexts record kernel and transfer times, see set_profiling()
+static PROFILING: LazyLock<Mutex<bool>> =
+    LazyLock::new(|| Mutex::new(::std::env::var_os("LINEAR_ALGEBRA_PROFILE").is_some()));
+
+/// Returns true if the default context uses the pure Rust backend instead of OpenCL
+///
+/// This is the case when the crate is built with the `no_opencl` feature or when no OpenCL
+/// device could be found.
+pub fn is_native() -> bool {
+    Context::default().is_native()
 }
 
-/// Get OpenCL queue and kernel parameters(work_group_size and global_work_size)
+/// Get OpenCL queue of the default context
 pub fn get_cl_data<T: Parameter>() -> ocl::Queue {
-    let data = cl_data::<T>();
-    data.queue.queue().clone()
+    Context::default().cl_queue().unwrap_or_else(|e| panic!("{}", e)).clone()
 }
 
 /// Get optimal work sizes for kernel
 pub fn get_work_sizes(kernel: &ocl::Kernel) -> KernelParams {
+    try_get_work_sizes(kernel).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Get optimal work sizes for kernel
+pub fn try_get_work_sizes(kernel: &ocl::Kernel) -> Result<KernelParams> {
     use ocl::enums::{ KernelWorkGroupInfoResult, KernelWorkGroupInfo };
 
     let result = kernel.wg_info(
-        ocl::Device::from(kernel.devices().unwrap()[0]),
+        ocl::Device::from(kernel.devices()?[0]),
         KernelWorkGroupInfo::PreferredWorkGroupSizeMultiple);
 
     let work_group_size =
         match result {
             KernelWorkGroupInfoResult::PreferredWorkGroupSizeMultiple(s) => s,
-            KernelWorkGroupInfoResult::Error(e) => panic!("{}", e),
-            _ => panic!(""),
+            KernelWorkGroupInfoResult::Error(e) => return Err(Error::OpenCl(*e)),
+            _ => return Err(Error::OpenCl("Unexpected work group info result".into())),
         };
-    let work_group_count = 64;                  //TODO: find a way to decide this automatically
+    let work_group_count = 64;                  // Untuned default, see tune for tuned sizes
     let global_work_size = work_group_count * work_group_size;
 
-    KernelParams{
+    Ok(KernelParams{
         work_group_size,
         work_group_count,
         global_work_size
-    }
+    })
 }
 
-/// Get all OpenCL data, internal use only
-fn cl_data<'a, T: Parameter>() -> MutexGuard<'a, OclData> {
-    lazy_static! {
-    static ref CL_DATA: Mutex<OclData> =
-    {
-        let types = &*TYPES.lock().unwrap();
 
-        let queue = unsafe { setup_queue(types) };
+/// Create Kernel object from kernel source registered with add_kernel_src()
+///
+/// Note:
+/// In kernel source, use {T} as type when referring to corresponding rust equivalent T.
+/// Variable name 'i' is defined as get_global_id(0)
+/// All functions should be prefixed with {T}_, for example {T}_add().
+/// This is because the source is compiled separately for every type the first time it is used.
+/// IS_{T} will be defined to the current type and can thus be used in preprocessor
+/// #ifdef conditions and similar to limit what code is compiled.
+///
+/// The kernel is created in the default context, use Context::create_kernel() for other contexts.
+pub fn create_kernel<T: Parameter>(kernel_name: &str) -> ocl::Kernel {
+    Context::default().create_kernel::<T>(kernel_name)
+}
 
-        Mutex::new( OclData {
-            queue,
-            kernels: HashMap::new(),
-        })
-    };
+/// Call the kernel {T}_kernel_name in the default context with typed arguments, see
+/// call::KernelCall
+pub fn kernel_call<'a, T: Parameter>(kernel_name: &str) -> call::KernelCall<'a, T> {
+    Context::default().kernel_call::<T>(kernel_name)
 }
 
-    let mut data = CL_DATA.lock().unwrap();
-    let ty = T::type_to_str().to_owned();
+/// Register extra kernel source, see create_kernel() for how to write it
+///
+/// The source is compiled for every context created after this call, including the default
+/// context if it has not been used yet. Use Context::add_kernel_src() to add source to a context
+/// that already exists.
+pub fn add_kernel_src(src: &str) {
+    KERNEL_SRC.lock().unwrap().push(src.to_owned());
+}
 
-    if !data.kernels.contains_key(&ty){
-        let kernels = unsafe{ setup_kernels::<T>(&data.queue) };
-        data.kernels.insert(ty, kernels);
+/// Build a program on the device of data from src templated for type ty
+fn build_program(data: &OclData, ty: &str, src: &str) -> Result<ocl::Program> {
+    let src = template_src(&vec![ty], &("#define i get_global_id(0)\n".to_owned() + src));
+    cache::build_program(&data.context, data.device, &src)
+        .map_err(|e| Error::Build(e.to_string()))
+}
+
+/// Compile the built in kernels and all registered source for type T
+///
+/// Registered source that fails to compile is recorded in Kernels::build_errors, it is only
+/// reported when creating a kernel.
+fn setup_type<T: Parameter>(data: &OclData) -> Result<Kernels> {
+    let ty = T::type_to_str();
+    check_type_supported(ty, data.device)?;
+
+    let src = template_src(&vec![ty], include_str!("kernels.cl"));
+    let program = cache::build_program(&data.context, data.device, &src)
+        .map_err(|e| Error::Build(e.to_string()))?;
+    let mut kernels = instantiate_kernels::<T>(data, program)?;
+
+    for src in &data.kernel_src {
+        match build_program(data, ty, src) {
+            Ok(program) => kernels.programs.push(program),
+            Err(Error::Build(log)) => kernels.build_errors.push(log),
+            Err(e) => return Err(e),
+        }
     }
 
-    data
+    Ok(kernels)
 }
 
-fn get_kernels<'a, 'b, T: Parameter>(name: &'b str) -> KernelsGuard<'a, 'b> {
-    let d = cl_data::<T>();
-    KernelsGuard(d, name)
+/// Create the built in kernels of type T from program
+///
+/// Every call creates new OpenCL kernel objects, so arguments set on them do not affect kernels
+/// created by other calls.
+fn instantiate_kernels<T: Parameter>(data: &OclData, program: ocl::Program) -> Result<Kernels> {
+    let queue = ProQue::new(data.context.clone(), data.queue.clone(), program, None::<usize>);
+    Ok(unsafe { setup_kernels::<T>(&queue)? })
 }
 
-/// Create Kernel object from kernel source in extra_kernels.cl
-///
-/// Note:
-/// In kernel source, use {T} as type when referring to corresponding rust equivalent T.
-/// Variable name 'i' is defined as get_global_id(0)
-/// All functions should be prefixed with {T}_, for example {T}_add().
-/// This is because all source will be compiled once for every type in TYPES vector.
-/// IS_{T} will be defined to the current type and can thus be used in preprocessor
-/// #ifdef conditions and similar to limit what code is compiled.
-pub fn create_kernel<T: Parameter>(kernel_name: &str) -> ocl::Kernel {
-    let queue = &mut cl_data::<T>().queue;
-    match queue.create_kernel(kernel_name) {
-        Ok(kernel) => kernel,
-        Err(error) => panic!("Failed to create kernel. Forgot to add kernel source to kernel.cl?: {}", error)
+/// Enables double precision on devices supporting it through either extension
+const FP64_PRAGMA: &str = "\
+#if defined(cl_khr_fp64)
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+#elif defined(cl_amd_fp64)
+#pragma OPENCL EXTENSION cl_amd_fp64 : enable
+#endif
+";
+
+/// Returns true if device supports double precision through cl_khr_fp64 or cl_amd_fp64
+pub fn supports_fp64(device: ocl::Device) -> bool {
+    use ocl::enums::DeviceInfo;
+
+    let extensions = device.info(DeviceInfo::Extensions).to_string();
+    extensions.contains("cl_khr_fp64") || extensions.contains("cl_amd_fp64")
+}
+
+/// Fails with Error::Unsupported if kernels for type ty can not be compiled for device
+fn check_type_supported(ty: &str, device: ocl::Device) -> Result<()> {
+    if ty == "double" && !supports_fp64(device) {
+        return Err(Error::Unsupported(format!(
+            "double requires cl_khr_fp64 or cl_amd_fp64 which {} does not support", device.name())));
     }
+    Ok(())
 }
 
-fn load_extra_src() -> Result<String, std::io::Error> {
-    use std::fs::File;
-    use std::io::prelude::*;
-    let mut file = File::open("src/extra_kernels.cl")?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents)
+/// Set the directory where compiled kernels are cached, None disables the cache
+///
+/// Defaults to the LINEAR_ALGEBRA_CACHE_DIR environment variable if set, otherwise
+/// linear_algebra_kernels in the system's temp directory. Only affects contexts created after
+/// this call.
+pub fn set_cache_dir<P: Into<std::path::PathBuf>>(dir: Option<P>) {
+    *cache::CACHE_DIR.lock().unwrap() = dir.map(|d| d.into());
 }
 
-unsafe fn setup_kernels<T: Parameter>(queue: &ProQue) -> Kernels {
+/// Enable or disable profiling of contexts created after this call, see profile
+///
+/// Defaults to enabled if the LINEAR_ALGEBRA_PROFILE environment variable is set. Profiling
+/// makes OpenCL record the device time of every command, which adds a small overhead.
+pub fn set_profiling(enabled: bool) {
+    *PROFILING.lock().unwrap() = enabled;
+}
 
-    #[cfg(test)]
-    println!("\nPicked device: {}, {}", queue.device().vendor(), queue.device().name());
+/// Set the file where tuned work group sizes are stored, None keeps them in memory only
+///
+/// Defaults to the LINEAR_ALGEBRA_TUNING_FILE environment variable if set, otherwise tuning.txt
+/// in linear_algebra_kernels in the system's temp directory. Work group sizes of sum, dot and
+/// matrix multiplication are benchmarked the first time they run for a device and size, later
+/// runs load the results from this file.
+pub fn set_tuning_file<P: Into<std::path::PathBuf>>(file: Option<P>) {
+    *tune::TUNING_FILE.lock().unwrap() = file.map(|f| f.into());
+    tune::reset();
+}
 
+unsafe fn setup_kernels<T: Parameter>(queue: &ProQue) -> ocl::Result<Kernels> {
     let type_prefix = T::type_to_str().to_owned() + "_";
 
-    let add_vec_vec = queue.create_kernel(&(type_prefix.clone() + "add_vec_vec")).unwrap()
+    let add_vec_vec = queue.create_kernel(&(type_prefix.clone() + "add_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let sub_vec_vec = queue.create_kernel(&(type_prefix.clone() + "sub_vec_vec")).unwrap()
+    let sub_vec_vec = queue.create_kernel(&(type_prefix.clone() + "sub_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let mul_vec_vec = queue.create_kernel(&(type_prefix.clone() + "mul_vec_vec")).unwrap()
+    let mul_vec_vec = queue.create_kernel(&(type_prefix.clone() + "mul_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let div_vec_vec = queue.create_kernel(&(type_prefix.clone() + "div_vec_vec")).unwrap()
+    let div_vec_vec = queue.create_kernel(&(type_prefix.clone() + "div_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let dot_vec_vec = queue.create_kernel(&(type_prefix.clone() + "dot_vec_vec")).unwrap()
+    let dot_vec_vec = queue.create_kernel(&(type_prefix.clone() + "dot_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("a", None)
         .arg_buf_named::<T, Buffer<T>>("b", None)
         .arg_buf_named::<T, Buffer<T>>("results", None)
         .arg_scl_named::<i32>("count", None);
 
 
-    let add_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "add_assign_vec_vec")).unwrap()
+    let add_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "add_assign_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let sub_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "sub_assign_vec_vec")).unwrap()
+    let sub_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "sub_assign_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let mul_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "mul_assign_vec_vec")).unwrap()
+    let mul_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "mul_assign_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
-    let div_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "div_assign_vec_vec")).unwrap()
+    let div_assign_vec_vec = queue.create_kernel(&(type_prefix.clone() + "div_assign_vec_vec"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
 
-    let mul_vec_scl = queue.create_kernel(&(type_prefix.clone() + "mul_vec_scl")).unwrap()
+    let mul_vec_scl = queue.create_kernel(&(type_prefix.clone() + "mul_vec_scl"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_scl_named::<T>("B", None);
-    let div_vec_scl = queue.create_kernel(&(type_prefix.clone() + "div_vec_scl")).unwrap()
+    let div_vec_scl = queue.create_kernel(&(type_prefix.clone() + "div_vec_scl"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_scl_named::<T>("B", None);
 
-    let mul_assign_vec_scl = queue.create_kernel(&(type_prefix.clone() + "mul_assign_vec_scl")).unwrap()
+    let mul_assign_vec_scl = queue.create_kernel(&(type_prefix.clone() + "mul_assign_vec_scl"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_scl_named::<T>("B", None);
-    let div_assign_vec_scl = queue.create_kernel(&(type_prefix.clone() + "div_assign_vec_scl")).unwrap()
+    let div_assign_vec_scl = queue.create_kernel(&(type_prefix.clone() + "div_assign_vec_scl"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_scl_named::<T>("B", None);
 
-    let eq_vec = queue.create_kernel(&(type_prefix.clone() + "eq_vec")).unwrap()
+    let eq_vec = queue.create_kernel(&(type_prefix.clone() + "eq_vec"))?
         .arg_buf_named::<u8, Buffer<u8>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None);
 
-    let sum_vec = queue.create_kernel(&(type_prefix.clone() + "sum_vec")).unwrap()
+    let sum_vec = queue.create_kernel(&(type_prefix.clone() + "sum_vec"))?
         .arg_buf_named::<T, Buffer<T>>("data", None)
         .arg_buf_named::<T, Buffer<T>>("results", None)
         .arg_scl_named::<i32>("count", None);
@@ -251,14 +461,14 @@ unsafe fn setup_kernels<T: Parameter>(queue: &ProQue) -> Kernels {
 
     //Matrix vec
 
-    let mul_vec_mat = queue.create_kernel(&(type_prefix.clone() + "mul_vec_mat")).unwrap()
+    let mul_vec_mat = queue.create_kernel(&(type_prefix.clone() + "mul_vec_mat"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None)
         .arg_scl_named::<i32>("B_col_count", None)
         .arg_scl_named::<i32>("A_len", None);
 
-    let mul_vec_transpose_mat = queue.create_kernel(&(type_prefix.clone() + "mul_vec_transpose_mat")).unwrap()
+    let mul_vec_transpose_mat = queue.create_kernel(&(type_prefix.clone() + "mul_vec_transpose_mat"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None)
@@ -266,14 +476,69 @@ unsafe fn setup_kernels<T: Parameter>(queue: &ProQue) -> Kernels {
         .arg_scl_named::<i32>("A_len", None);
     //Matrix
 
-    let mul_mat_mat = queue.create_kernel(&(type_prefix.clone() + "mul_mat_mat")).unwrap()
+    let mul_mat_mat = queue.create_kernel(&(type_prefix.clone() + "mul_mat_mat"))?
         .arg_buf_named::<T, Buffer<T>>("C", None)
         .arg_buf_named::<T, Buffer<T>>("A", None)
         .arg_buf_named::<T, Buffer<T>>("B", None)
         .arg_scl_named::<i32>("C_col_count", None)
         .arg_scl_named::<i32>("A_col_count", None);
 
-    Kernels {
+    let set_diagonal = queue.create_kernel(&(type_prefix.clone() + "set_diagonal"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<i32>("col_count", None)
+        .arg_scl_named::<T>("value", None);
+
+    //Random
+
+    let rng_uniform = queue.create_kernel(&(type_prefix.clone() + "rng_uniform"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<u64>("seed", None)
+        .arg_scl_named::<u64>("stream", None)
+        .arg_scl_named::<T>("low", None)
+        .arg_scl_named::<T>("high", None)
+        .arg_scl_named::<u64>("range", None);
+
+    let rng_normal = queue.create_kernel(&(type_prefix.clone() + "rng_normal"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<u64>("seed", None)
+        .arg_scl_named::<u64>("stream", None)
+        .arg_scl_named::<T>("mean", None)
+        .arg_scl_named::<T>("std_dev", None);
+
+    let rng_bernoulli = queue.create_kernel(&(type_prefix.clone() + "rng_bernoulli"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<u64>("seed", None)
+        .arg_scl_named::<u64>("stream", None)
+        .arg_scl_named::<u64>("threshold", None);
+
+    //Generators
+
+    let arange = queue.create_kernel(&(type_prefix.clone() + "arange"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<T>("start", None)
+        .arg_scl_named::<T>("step", None);
+
+    let linspace = queue.create_kernel(&(type_prefix.clone() + "linspace"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<T>("start", None)
+        .arg_scl_named::<T>("step", None)
+        .arg_scl_named::<T>("stop", None)
+        .arg_scl_named::<u64>("last", None);
+
+    let logspace = queue.create_kernel(&(type_prefix.clone() + "logspace"))?
+        .arg_buf_named::<T, Buffer<T>>("C", None)
+        .arg_scl_named::<T>("start", None)
+        .arg_scl_named::<T>("step", None)
+        .arg_scl_named::<T>("stop", None)
+        .arg_scl_named::<u64>("last", None)
+        .arg_scl_named::<T>("base", None);
+
+    let placeholders = (
+        Buffer::<T>::builder().queue(queue.queue().clone()).dims(1).build()?,
+        Buffer::<u8>::builder().queue(queue.queue().clone()).dims(1).build()?,
+    );
+
+    Ok(Kernels {
         add_vec_vec,
         sub_vec_vec,
         mul_vec_vec,
@@ -302,50 +567,40 @@ unsafe fn setup_kernels<T: Parameter>(queue: &ProQue) -> Kernels {
         //Matrix
 
         mul_mat_mat,
+        set_diagonal,
 
-        queue: queue.queue().clone(),
-    }
-}
+        //Random
 
-unsafe fn setup_queue(types: &Vec<&str>) -> ProQue {
-    let mut builder = ProQue::builder();
-
-    let queue = if let Some((p, d)) = get_gpu() {
-        builder
-            .platform(p)
-            .device(d)
-    } else {
-        let (p, d) = get_cpu().unwrap();
-        builder
-            .platform(p)
-            .device(d)
-    };
-
-
-    let src = get_src(types);
-    let q = match queue
-        .src(src)
-        .build()
-        {
-            Err(e) => panic!("Failed to compile kernels with error: {}", e),
-            Ok(q) => q,
-        };
+        rng_uniform,
+        rng_normal,
+        rng_bernoulli,
+
+        //Generators
+
+        arange,
+        linspace,
+        logspace,
+
+        program: queue.program().clone(),
+        programs: Vec::new(),
+        build_errors: Vec::new(),
 
-    q
+        placeholders: Box::new(placeholders),
+        unbind: unbind_buffers::<T>,
+    })
 }
 
-fn get_src(types: &Vec<&str>) -> String {
+/// Repeat src once for every type with {T} replaced by the type and IS_{T} defined
+fn template_src(types: &Vec<&str>, src: &str) -> String {
     let mut res = String::new();
     for ty in types {
-        let extra_src = load_extra_src().unwrap_or_default();
-
         let header = format!("#define IS_{}\n", ty.to_uppercase());
-        let src = if ty == &"double" {
-            "#pragma OPENCL EXTENSION cl_khr_fp64 : enable\n"
+        let pragma = if ty == &"double" {
+            FP64_PRAGMA
         } else {
             ""
         };
-        let src = header + src + include_str!("kernels.cl") + "\n" + &extra_src;
+        let src = header + pragma + src + "\n";
         res += &src.replace("{T}", ty);
         res += &format!("\n#undef IS_{}\n", ty.to_uppercase());
     }
@@ -353,19 +608,32 @@ fn get_src(types: &Vec<&str>) -> String {
     res
 }
 
+/// List all OpenCL platforms, unlike ocl::Platform::list() this does not panic if there are none
+///
+/// The list is only queried once since that may take several seconds when no platform is found.
+fn platforms() -> Vec<ocl::Platform> {
+    static PLATFORMS: LazyLock<Vec<ocl::Platform>> = LazyLock::new(|| {
+        match ocl::core::get_platform_ids() {
+            Ok(ids) => ocl::Platform::list_from_core(ids),
+            Err(_) => Vec::new(),
+        }
+    });
+
+    PLATFORMS.clone()
+}
+
 fn get_gpu() -> Option<(ocl::Platform, ocl::Device)> {
     if cfg!(feature = "no_gpu") {
         return None;
     }
 
-    let platforms = ocl::Platform::list();
-    for platform in platforms {
+    for platform in platforms() {
         let devices =
             match ocl::Device::list(platform, Some(ocl::flags::DEVICE_TYPE_GPU)) {
                 Ok(d) => d,
                 Err(_) => continue,
             };
-        for d in devices {
+        if let Some(d) = devices.into_iter().next() {
             return Some((platform, d));
         }
     }
@@ -373,14 +641,13 @@ fn get_gpu() -> Option<(ocl::Platform, ocl::Device)> {
 }
 
 fn get_cpu() -> Option<(ocl::Platform, ocl::Device)> {
-    let platforms = ocl::Platform::list();
-    for platform in platforms {
+    for platform in platforms() {
         let devices =
             match ocl::Device::list(platform, Some(ocl::flags::DEVICE_TYPE_ALL)) {
                 Ok(d) => d,
                 Err(_) => continue,
             };
-        for d in devices {
+        if let Some(d) = devices.into_iter().next() {
             return Some((platform, d));
         }
     }
@@ -1,10 +1,16 @@
 
 extern crate ocl;
 
-use get_kernels;
+use context::Context;
+use error::{ Error, Result };
+use expr::{ self, MatrixExpr };
+use native;
+use tune;
 use traits::Parameter;
 use util::*;
 use vector::*;
+use transfer::Transfer;
+use random::{ Random, RandomNormal, Rng };
 
 pub struct Matrix<T: Parameter> {
     pub(crate) data: Vector<T>,
@@ -16,41 +22,347 @@ impl<T: Parameter> Matrix<T> {
     pub fn new(default_value: T, row_count: usize, col_count: usize) -> Matrix<T>
         where T: Copy
     {
-        Matrix {
-            data: Vector::new(default_value, row_count * col_count),
+        Matrix::new_in(default_value, row_count, col_count, &Context::default())
+    }
+
+    /// Same as new() but creates the matrix in the specified context
+    pub fn new_in(default_value: T, row_count: usize, col_count: usize, context: &Context) -> Matrix<T>
+        where T: Copy
+    {
+        Matrix::filled_in(default_value, row_count, col_count, context)
+    }
+
+    /// Create matrix with every element set to value, the buffer is filled on the device
+    /// without copying from the host
+    pub fn filled(value: T, row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::filled_in(value, row_count, col_count, &Context::default())
+    }
+
+    /// Same as filled() but creates the matrix in the specified context
+    pub fn filled_in(value: T, row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_filled_in(value, row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as filled_in() but returns an error if the buffer could not be allocated
+    pub fn try_filled_in(value: T, row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        Ok(Matrix {
+            data: Vector::try_filled_in(value, row_count * col_count, context)?,
             row_count,
             col_count
+        })
+    }
+
+    /// Create matrix with every element set to 0
+    pub fn zeros(row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::filled(T::ZERO, row_count, col_count)
+    }
+
+    /// Same as zeros() but creates the matrix in the specified context
+    pub fn zeros_in(row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::filled_in(T::ZERO, row_count, col_count, context)
+    }
+
+    /// Create matrix with every element set to 1
+    pub fn ones(row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::filled(T::ONE, row_count, col_count)
+    }
+
+    /// Same as ones() but creates the matrix in the specified context
+    pub fn ones_in(row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::filled_in(T::ONE, row_count, col_count, context)
+    }
+
+    /// Create size x size identity matrix
+    pub fn identity(size: usize) -> Matrix<T> {
+        Matrix::identity_in(size, &Context::default())
+    }
+
+    /// Same as identity() but creates the matrix in the specified context
+    pub fn identity_in(size: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_identity_in(size, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as identity_in() but returns an error if the buffer could not be allocated
+    pub fn try_identity_in(size: usize, context: &Context) -> Result<Matrix<T>> {
+        if context.is_native() {
+            let v = (0..size * size).map(|idx| if idx / size == idx % size { T::ONE } else { T::ZERO }).collect();
+            return Matrix::try_from_vec_in(v, size, size, context);
+        }
+
+        let mut res = Matrix::try_filled_in(T::ZERO, size, size, context)?;
+        if size == 0 {
+            return Ok(res);
+        }
+
+        let mut kernels = context.kernels::<T>()?;
+        let kernel = &mut kernels.set_diagonal;
+
+        kernel.set_arg_buf_named("C", Some(res.data.buf_mut()))?;
+        kernel.set_arg_scl_named::<i32>("col_count", size as i32)?;
+        kernel.set_arg_scl_named("value", T::ONE)?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(context.cl_queue()?).ewait(&res.data.write_wait_list()).enew(&mut event).gws(size).enq()?;
+            context.profile_kernel(kernel, &event);
+            res.data.set_event(event);
         }
+        Ok(res)
     }
 
+    /// # Safety
+    ///
+    /// The elements have arbitrary values, they have to be written before they are read.
     pub unsafe fn uninitialized(row_count: usize, col_count: usize) -> Matrix<T> {
-        let queue = get_kernels::<T>(T::type_to_str()).queue.clone();
-        Matrix::<T>::uninitialized_lock_free(row_count, col_count, queue)
+        Matrix::uninitialized_in(row_count, col_count, &Context::default())
     }
 
-    unsafe fn uninitialized_lock_free(row_count: usize, col_count: usize, queue: ocl::Queue) -> Matrix<T> {
-        Matrix {
-            data: Vector::uninitialized_lock_free(row_count * col_count, queue),
+    /// Same as uninitialized() but creates the matrix in the specified context
+    ///
+    /// # Safety
+    ///
+    /// See uninitialized().
+    pub unsafe fn uninitialized_in(row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_uninitialized_in(row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as uninitialized_in() but returns an error if the buffer could not be allocated
+    ///
+    /// # Safety
+    ///
+    /// See uninitialized().
+    pub unsafe fn try_uninitialized_in(row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        Ok(Matrix {
+            data: Vector::try_uninitialized_in(row_count * col_count, context)?,
             row_count,
             col_count
-        }
+        })
     }
 
     pub fn from_vec(v: Vec<T>, row_count: usize, col_count: usize) -> Matrix<T> {
-        assert_eq!(v.len(), row_count * col_count);
-        Matrix {
-            data: Vector::from_vec(v),
+        Matrix::from_vec_in(v, row_count, col_count, &Context::default())
+    }
+
+    /// Same as from_vec() but creates the matrix in the specified context
+    pub fn from_vec_in(v: Vec<T>, row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_from_vec_in(v, row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_vec() but returns an error instead of panicking
+    pub fn try_from_vec(v: Vec<T>, row_count: usize, col_count: usize) -> Result<Matrix<T>> {
+        Matrix::try_from_vec_in(v, row_count, col_count, &Context::try_default()?)
+    }
+
+    /// Same as from_vec_in() but returns an error if v does not have row_count * col_count
+    /// elements or if the buffer could not be allocated
+    pub fn try_from_vec_in(v: Vec<T>, row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        if v.len() != row_count * col_count {
+            return Err(Error::shape_mismatch(v.len(), row_count * col_count));
+        }
+        Ok(Matrix {
+            data: Vector::try_from_vec_in(v, context)?,
             row_count,
             col_count
-        }
+        })
+    }
+
+    /// Same as from_vec() but copies the elements from a slice in row major order
+    pub fn from_slice(src: &[T], row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::from_slice_in(src, row_count, col_count, &Context::default())
     }
 
-    pub fn generate<F: FnMut(usize)-> T>(kernel: &mut ocl::Kernel, row_count: usize, col_count: usize) -> Matrix<T> {
-        Matrix {
-            data: Vector::generate(kernel, row_count * col_count),
+    /// Same as from_slice() but creates the matrix in the specified context
+    pub fn from_slice_in(src: &[T], row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_from_slice_in(src, row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_slice_in() but returns an error if src does not have row_count * col_count
+    /// elements or if the buffer could not be allocated
+    pub fn try_from_slice_in(src: &[T], row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        if src.len() != row_count * col_count {
+            return Err(Error::shape_mismatch(src.len(), row_count * col_count));
+        }
+        Ok(Matrix {
+            data: Vector::try_from_slice_in(src, context)?,
             row_count,
             col_count
+        })
+    }
+
+    /// Wrap an existing buffer of the default context holding the elements in row major order
+    /// without copying it, see Vector::from_buffer_in()
+    pub fn from_buffer(buffer: ocl::Buffer<T>, row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::from_buffer_in(buffer, row_count, col_count, &Context::default())
+    }
+
+    /// Same as from_buffer() but wraps a buffer of the specified context
+    pub fn from_buffer_in(buffer: ocl::Buffer<T>, row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_from_buffer_in(buffer, row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_buffer_in() but returns an error if buffer does not have
+    /// row_count * col_count elements or belongs to another OpenCL context
+    pub fn try_from_buffer_in(buffer: ocl::Buffer<T>, row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        if buffer.len() != row_count * col_count {
+            return Err(Error::shape_mismatch(buffer.len(), row_count * col_count));
         }
+        Ok(Matrix {
+            data: Vector::try_from_buffer_in(buffer, context)?,
+            row_count,
+            col_count
+        })
+    }
+
+    /// Unwrap the buffer of the matrix, see Vector::into_buffer()
+    pub fn into_buffer(self) -> Result<ocl::Buffer<T>> {
+        self.data.into_buffer()
+    }
+
+    /// Copy all elements in row major order into dst, which has to have row_count * col_count
+    /// elements
+    pub fn read_into(&self, dst: &mut [T]) {
+        self.data.read_into(dst)
+    }
+
+    /// Same as read_into() but returns an error instead of panicking
+    pub fn try_read_into(&self, dst: &mut [T]) -> Result<()> {
+        self.data.try_read_into(dst)
+    }
+
+    /// Copy dst.len() elements starting at the row major index offset into dst
+    pub fn read_range_into(&self, offset: usize, dst: &mut [T]) {
+        self.data.read_range_into(offset, dst)
+    }
+
+    /// Same as read_range_into() but returns an error instead of panicking
+    pub fn try_read_range_into(&self, offset: usize, dst: &mut [T]) -> Result<()> {
+        self.data.try_read_range_into(offset, dst)
+    }
+
+    /// Read all elements in row major order without waiting for the data, see Transfer
+    pub fn to_vec_async(&self) -> Transfer<T> {
+        self.data.to_vec_async()
+    }
+
+    /// Same as to_vec_async() but returns an error if the read could not be enqueued
+    pub fn try_to_vec_async(&self) -> Result<Transfer<T>> {
+        self.data.try_to_vec_async()
+    }
+
+    /// Same as read_into() but returns without waiting for the data, dst is handed back by the
+    /// returned Transfer
+    pub fn read_into_async(&self, dst: Vec<T>) -> Transfer<T> {
+        self.data.read_into_async(dst)
+    }
+
+    /// Same as read_into_async() but returns an error instead of panicking
+    pub fn try_read_into_async(&self, dst: Vec<T>) -> Result<Transfer<T>> {
+        self.data.try_read_into_async(dst)
+    }
+
+    /// Overwrite all elements with src in row major order, which has to have
+    /// row_count * col_count elements
+    pub fn write_from(&mut self, src: &[T]) {
+        self.data.write_from(src)
+    }
+
+    /// Same as write_from() but returns an error instead of panicking
+    pub fn try_write_from(&mut self, src: &[T]) -> Result<()> {
+        self.data.try_write_from(src)
+    }
+
+    /// Overwrite src.len() elements starting at the row major index offset with src
+    pub fn write_range_from(&mut self, offset: usize, src: &[T]) {
+        self.data.write_range_from(offset, src)
+    }
+
+    /// Same as write_range_from() but returns an error instead of panicking
+    pub fn try_write_range_from(&mut self, offset: usize, src: &[T]) -> Result<()> {
+        self.data.try_write_range_from(offset, src)
+    }
+
+    pub fn generate(kernel: &mut ocl::Kernel, row_count: usize, col_count: usize) -> Matrix<T> {
+        Matrix::generate_in(kernel, row_count, col_count, &Context::default())
+    }
+
+    /// Same as generate() but creates the matrix in the specified context, kernel has to belong
+    /// to that context as well
+    pub fn generate_in(kernel: &mut ocl::Kernel, row_count: usize, col_count: usize, context: &Context) -> Matrix<T> {
+        Matrix::try_generate_in(kernel, row_count, col_count, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as generate_in() but returns an error instead of panicking
+    pub fn try_generate_in(kernel: &mut ocl::Kernel, row_count: usize, col_count: usize, context: &Context) -> Result<Matrix<T>> {
+        Ok(Matrix {
+            data: Vector::try_generate_in(kernel, row_count * col_count, context)?,
+            row_count,
+            col_count
+        })
+    }
+
+    /// Create matrix with every element computed by the OpenCL C expression expr from its row
+    /// index r and column index c, e.g. "r * 2 + c"
+    ///
+    /// The kernel is compiled on first use and cached by type and expression, the native
+    /// backend only supports + - * /, parentheses and numbers, see Vector::map().
+    pub fn from_index_expr(row_count: usize, col_count: usize, expr: &str) -> Matrix<T>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        Matrix::from_index_expr_in(row_count, col_count, expr, &Context::default())
+    }
+
+    /// Same as from_index_expr() but creates the matrix in the specified context
+    pub fn from_index_expr_in(row_count: usize, col_count: usize, expr: &str, context: &Context) -> Matrix<T>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        Matrix::try_from_index_expr_in(row_count, col_count, expr, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_index_expr_in() but returns an error instead of panicking, fails with
+    /// Error::Build if expr does not compile
+    pub fn try_from_index_expr_in(row_count: usize, col_count: usize, expr: &str, context: &Context) -> Result<Matrix<T>>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        Ok(Matrix {
+            data: expr::eval_index_str(context, row_count, col_count, expr)?,
+            row_count,
+            col_count
+        })
+    }
+
+    /// Matrix with the elements of data in row major order, data has to have
+    /// row_count * col_count elements
+    pub(crate) fn from_data(data: Vector<T>, row_count: usize, col_count: usize) -> Matrix<T> {
+        debug_assert_eq!(data.len(), row_count * col_count);
+        Matrix { data, row_count, col_count }
+    }
+
+    /// Get the context this matrix lives in
+    pub fn context(&self) -> &Context {
+        self.data.context()
+    }
+
+    /// Move the matrix to another stream of its context, see Vector::set_stream()
+    pub fn set_stream(&mut self, stream: &Context) -> Result<()> {
+        self.data.set_stream(stream)
+    }
+
+    /// Events of the commands writing to this matrix which might not have completed yet
+    pub fn events(&self) -> &ocl::EventList {
+        self.data.events()
+    }
+
+    /// Block until all commands writing to this matrix have completed
+    pub fn finish(&self) {
+        self.data.finish()
+    }
+
+    /// Same as finish() but returns an error instead of panicking
+    pub fn try_finish(&self) -> Result<()> {
+        self.data.try_finish()
     }
 
     pub fn len(&self) -> (usize, usize) {
@@ -65,10 +377,22 @@ impl<T: Parameter> Matrix<T> {
         self.col_count
     }
 
+    /// Start a lazy expression, see expr::MatrixExpr
+    pub fn lazy<'a>(&'a self) -> MatrixExpr<'a, T> {
+        MatrixExpr::from_matrix(self)
+    }
+
+    /// # Safety
+    ///
+    /// Commands using the buffer bypass the event tracking of the matrix, they have to wait for
+    /// events() first and the matrix must not be used before they completed.
     pub unsafe fn get_buffer(&self) -> &ocl::Buffer<T> {
         self.data.get_buffer()
     }
 
+    /// # Safety
+    ///
+    /// See get_buffer().
     pub unsafe fn get_buffer_mut(&mut self) -> &mut ocl::Buffer<T> {
         self.data.get_buffer_mut()
     }
@@ -79,7 +403,11 @@ impl<T: Parameter> Matrix<T> {
     /// Two u64 will be placed first, representing the number of bytes per element and the
     /// number of elements respectively.
     pub fn write_to_file(&self, file: &mut ::std::fs::File) -> ::std::result::Result<(), ::std::io::Error> {
+        self.try_write_to_file(file).map_err(into_io_error)
+    }
 
+    /// Same as write_to_file() but returns the crate's Error type
+    pub fn try_write_to_file(&self, file: &mut ::std::fs::File) -> Result<()> {
         write_u64(file, ::std::mem::size_of::<T>() as  u64)?;         //Store element size in bytes
 
         write_u64(file, self.row_count as u64)?;                      //Store row count
@@ -90,14 +418,23 @@ impl<T: Parameter> Matrix<T> {
 
     /// Read data to from file.
     /// NOTE! Buffer will be interpreted in the current systems endianness
-    pub unsafe fn read_from_file(file: &mut ::std::fs::File) -> Result<Matrix<T>, ::std::io::Error> {
-        let elem_size = read_u64(file)?;
-
+    ///
+    /// # Safety
+    ///
+    /// The bytes in the file are reinterpreted as elements of T, the file has to be written by
+    /// write_to_file() for the same type.
+    pub unsafe fn read_from_file(file: &mut ::std::fs::File) -> ::std::result::Result<Matrix<T>, ::std::io::Error> {
+        Self::try_read_from_file(file).map_err(into_io_error)
+    }
 
-        assert_eq!(elem_size as usize, ::std::mem::size_of::<T>(),
-                   "Elem size from buffer does not seem to match, what was expected!\
-                 Missmatch in endiannes?"
-        );
+    /// Same as read_from_file() but returns an error instead of panicking if the element size
+    /// in the file does not match
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn try_read_from_file(file: &mut ::std::fs::File) -> Result<Matrix<T>> {
+        check_elem_size::<T>(read_u64(file)?)?;
 
         let row_count = read_u64(file)? as usize;
         let col_count = read_u64(file)? as usize;
@@ -111,45 +448,222 @@ impl<T: Parameter> Matrix<T> {
 
     /// Save Vector to specified path.
     /// NOTE! The file will be encoded in the current systems endianness
-    pub fn save(&self, path: &str) -> Result<(), ::std::io::Error> {
+    pub fn save(&self, path: &str) -> ::std::result::Result<(), ::std::io::Error> {
+        self.try_save(path).map_err(into_io_error)
+    }
+
+    /// Same as save() but returns the crate's Error type
+    pub fn try_save(&self, path: &str) -> Result<()> {
         use std::fs::File;
 
         let mut file = File::create(path)?;
 
-        self.write_to_file(&mut file)
+        self.try_write_to_file(&mut file)
     }
 
     /// Open Vector from specified path.
     /// NOTE! The file will be interpreted in the current systems endianness
-    pub unsafe fn open(path: &str) -> Result<Matrix<T>, ::std::io::Error> {
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn open(path: &str) -> ::std::result::Result<Matrix<T>, ::std::io::Error> {
+        Self::try_open(path).map_err(into_io_error)
+    }
+
+    /// Same as open() but returns the crate's Error type
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn try_open(path: &str) -> Result<Matrix<T>> {
         use std::fs::File;
 
-        let mut file = File::open(path).expect("Failed to open file");
+        let mut file = File::open(path)?;
 
-        Ok(Self::read_from_file(&mut file).expect("Failed to read all data from file"))
+        Self::try_read_from_file(&mut file)
     }
-}
 
+    /// Fails with Error::ShapeMismatch if other does not have the same dimensions as self
+    fn check_shape(&self, other: &Matrix<T>) -> Result<()> {
+        if self.len() == other.len() {
+            Ok(())
+        } else {
+            Err(Error::mat_shape_mismatch(self.len(), other.len()))
+        }
+    }
 
-impl<'a, 'b, T: Parameter + ::std::ops::Add<T, Output=T>> ::std::ops::Add<&'b Matrix<T>> for &'a Matrix<T> {
-    type Output = Matrix<T>;
-    fn add(self, other: &'b Matrix<T>) -> Matrix<T> {
-        assert_eq!(self.row_count, other.row_count);
-        assert_eq!(self.col_count, other.col_count);
+    /// Compute the OpenCL C expression expr for every element x, see Vector::map()
+    pub fn map(&self, expr: &str) -> Matrix<T>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        self.try_map(expr).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as map() but returns an error instead of panicking
+    pub fn try_map(&self, expr: &str) -> Result<Matrix<T>>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        Ok(Matrix {
+            data: self.data.try_map(expr)?,
+            row_count: self.row_count,
+            col_count: self.col_count
+        })
+    }
+
+    /// Compute expr for every pair of elements a of self and b of other, see Vector::zip_with()
+    pub fn zip_with(&self, other: &Matrix<T>, expr: &str) -> Matrix<T>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        self.try_zip_with(other, expr).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        Matrix {
-            data: &self.data + &other.data,
+    /// Same as zip_with() but returns an error instead of panicking
+    pub fn try_zip_with(&self, other: &Matrix<T>, expr: &str) -> Result<Matrix<T>>
+        where T: ::std::ops::Add<T, Output=T> + ::std::ops::Sub<T, Output=T> +
+                 ::std::ops::Mul<T, Output=T> + ::std::ops::Div<T, Output=T>
+    {
+        self.check_shape(other)?;
+        Ok(Matrix {
+            data: self.data.try_zip_with(&other.data, expr)?,
             row_count: self.row_count,
             col_count: self.col_count
+        })
+    }
+
+    /// Overwrite all elements with uniform random numbers, see Vector::fill_uniform()
+    pub fn fill_uniform(&mut self, rng: &mut Rng, low: T, high: T)
+        where T: Random
+    {
+        self.data.fill_uniform(rng, low, high)
+    }
+
+    /// Same as fill_uniform() but returns an error instead of panicking
+    pub fn try_fill_uniform(&mut self, rng: &mut Rng, low: T, high: T) -> Result<()>
+        where T: Random
+    {
+        self.data.try_fill_uniform(rng, low, high)
+    }
+
+    /// Overwrite all elements with normal distributed random numbers, see Vector::fill_normal()
+    pub fn fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T)
+        where T: RandomNormal
+    {
+        self.data.fill_normal(rng, mean, std_dev)
+    }
+
+    /// Same as fill_normal() but returns an error instead of panicking
+    pub fn try_fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T) -> Result<()>
+        where T: RandomNormal
+    {
+        self.data.try_fill_normal(rng, mean, std_dev)
+    }
+
+    /// Overwrite every element with one with probability p and zero otherwise, see
+    /// Vector::fill_bernoulli()
+    pub fn fill_bernoulli(&mut self, rng: &mut Rng, p: f64) {
+        self.data.fill_bernoulli(rng, p)
+    }
+
+    /// Same as fill_bernoulli() but returns an error instead of panicking
+    pub fn try_fill_bernoulli(&mut self, rng: &mut Rng, p: f64) -> Result<()> {
+        self.data.try_fill_bernoulli(rng, p)
+    }
+
+    /// Same as `self + other` but returns an error instead of panicking
+    pub fn try_add(&self, other: &Matrix<T>) -> Result<Matrix<T>>
+        where T: ::std::ops::Add<T, Output=T>
+    {
+        self.check_shape(other)?;
+        Ok(Matrix {
+            data: self.data.try_add(&other.data)?,
+            row_count: self.row_count,
+            col_count: self.col_count
+        })
+    }
+
+    /// Same as `self - other` but returns an error instead of panicking
+    pub fn try_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>>
+        where T: ::std::ops::Sub<T, Output=T>
+    {
+        self.check_shape(other)?;
+        Ok(Matrix {
+            data: self.data.try_sub(&other.data)?,
+            row_count: self.row_count,
+            col_count: self.col_count
+        })
+    }
+
+    /// Same as `self += other` but returns an error instead of panicking
+    pub fn try_add_assign(&mut self, other: &Matrix<T>) -> Result<()>
+        where T: ::std::ops::AddAssign<T>
+    {
+        self.check_shape(other)?;
+        self.data.try_add_assign(&other.data)
+    }
+
+    /// Same as `self -= other` but returns an error instead of panicking
+    pub fn try_sub_assign(&mut self, other: &Matrix<T>) -> Result<()>
+        where T: ::std::ops::SubAssign<T>
+    {
+        self.check_shape(other)?;
+        self.data.try_sub_assign(&other.data)
+    }
+
+    /// Same as `self * scalar` but returns an error instead of panicking
+    pub fn try_mul_scl(&self, scalar: T) -> Result<Matrix<T>>
+        where T: ::std::ops::Mul<T, Output=T>
+    {
+        Ok(Matrix {
+            data: self.data.try_mul_scl(scalar)?,
+            row_count: self.row_count,
+            col_count: self.col_count
+        })
+    }
+
+    /// Same as `self *= scalar` but returns an error instead of panicking
+    pub fn try_mul_assign_scl(&mut self, scalar: T) -> Result<()>
+        where T: ::std::ops::MulAssign<T>
+    {
+        self.data.try_mul_assign_scl(scalar)
+    }
+
+    /// Same as `self / scalar` but returns an error instead of panicking
+    pub fn try_div_scl(&self, scalar: T) -> Result<Matrix<T>>
+        where T: ::std::ops::Div<T, Output=T>
+    {
+        Ok(Matrix {
+            data: self.data.try_div_scl(scalar)?,
+            row_count: self.row_count,
+            col_count: self.col_count
+        })
+    }
+
+    /// Same as `self * other` but returns an error instead of panicking
+    pub fn try_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>>
+        where T: ::std::ops::Mul<T, Output=T> + ::std::ops::Add<T, Output=T>
+    {
+        if self.col_count != other.row_count {
+            return Err(Error::mat_shape_mismatch(self.len(), other.len()));
         }
+        mul_helper(&self.data, &other.data, self.row_count, self.col_count, other.col_count)
+    }
+
+    /// Same as `self == other` but returns an error instead of panicking
+    pub fn try_eq(&self, other: &Matrix<T>) -> Result<bool> {
+        Ok(self.row_count == other.row_count &&
+            self.col_count == other.col_count &&
+            self.data.try_eq(&other.data)?)
     }
 }
 
+
 impl<'a, T: Parameter + ::std::ops::AddAssign<T>> ::std::ops::AddAssign<&'a Matrix<T>> for Matrix<T> {
     fn add_assign(&mut self, other: &'a Matrix<T>) {
-        assert_eq!(self.row_count, other.row_count);
-        assert_eq!(self.col_count, other.col_count);
-        self.data += &other.data;
+        self.try_add_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -161,25 +675,9 @@ impl<'a, T: Parameter + ::std::ops::AddAssign<T>> ::std::ops::Add<&'a Matrix<T>>
     }
 }
 
-impl<'a, 'b, T: Parameter + ::std::ops::Sub<T, Output=T>> ::std::ops::Sub<&'b Matrix<T>> for &'a Matrix<T> {
-    type Output = Matrix<T>;
-    fn sub(self, other: &'b Matrix<T>) -> Matrix<T> {
-        assert_eq!(self.row_count, other.row_count);
-        assert_eq!(self.col_count, other.col_count);
-
-        Matrix {
-            data: &self.data - &other.data,
-            row_count: self.row_count,
-            col_count: self.col_count
-        }
-    }
-}
-
 impl<'a, T: Parameter + ::std::ops::SubAssign<T>> ::std::ops::SubAssign<&'a Matrix<T>> for Matrix<T> {
     fn sub_assign(&mut self, other: &'a Matrix<T>) {
-        assert_eq!(self.row_count, other.row_count);
-        assert_eq!(self.col_count, other.col_count);
-        self.data -= &other.data;
+        self.try_sub_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -191,25 +689,13 @@ impl<'a, T: Parameter + ::std::ops::SubAssign<T>> ::std::ops::Sub<&'a Matrix<T>>
     }
 }
 
-//Mul with scalar
-impl<'a, 'b, T: Parameter + ::std::ops::Mul<T, Output=T>> ::std::ops::Mul<T> for &'a Matrix<T> {
-    type Output = Matrix<T>;
-    fn mul(self, scalar: T) -> Matrix<T> {
-        Matrix {
-            data: &self.data * scalar,
-            row_count: self.row_count,
-            col_count: self.col_count
-        }
-    }
-}
-
-impl<'a, 'b, T: Parameter + ::std::ops::MulAssign<T>> ::std::ops::MulAssign<T> for Matrix<T> {
+impl<T: Parameter + ::std::ops::MulAssign<T>> ::std::ops::MulAssign<T> for Matrix<T> {
     fn mul_assign(&mut self, scalar: T) {
-        self.data *= scalar;
+        self.try_mul_assign_scl(scalar).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
-impl<'a, 'b, T: Parameter + ::std::ops::MulAssign<T>> ::std::ops::Mul<T> for Matrix<T> {
+impl<T: Parameter + ::std::ops::MulAssign<T>> ::std::ops::Mul<T> for Matrix<T> {
     type Output = Matrix<T>;
     fn mul(mut self, scalar: T) -> Matrix<T> {
         self *= scalar;
@@ -218,7 +704,7 @@ impl<'a, 'b, T: Parameter + ::std::ops::MulAssign<T>> ::std::ops::Mul<T> for Mat
 }
 
 
-impl<'a, 'b, T> ::std::ops::Mul<&'b Matrix<T>> for &'a Matrix<T>
+impl<'b, T> ::std::ops::Mul<&'b Matrix<T>> for &Matrix<T>
     where T:
     Parameter +
         ::std::ops::Mul<T, Output=T> +
@@ -226,63 +712,84 @@ impl<'a, 'b, T> ::std::ops::Mul<&'b Matrix<T>> for &'a Matrix<T>
 {
     type Output = Matrix<T>;
     fn mul(self, other: &'b Matrix<T>) -> Matrix<T> {
-        assert_eq!(self.col_count, other.row_count);
-        mul_helper(&self.data.data, &other.data.data, self.row_count, self.col_count, other.col_count)
+        self.try_mul(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 /// Multiplies two vectors as if they where one one-column-matrix and one one-row-matrix respectively
 /// resulting in a matrix with row.len() columns and col.len() rows
-pub fn mul_column_row<T: Parameter + ::std::ops::Mul<T, Output=T>>(column: &Vector<T>, row: &Vector<T>) -> Matrix<T> {
-    mul_helper(&column.data, &row.data, column.len(), 1, row.len())
+pub fn mul_column_row<T>(column: &Vector<T>, row: &Vector<T>) -> Matrix<T>
+    where T: Parameter + ::std::ops::Mul<T, Output=T> + ::std::ops::Add<T, Output=T>
+{
+    try_mul_column_row(column, row).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as mul_column_row() but returns an error instead of panicking
+pub fn try_mul_column_row<T>(column: &Vector<T>, row: &Vector<T>) -> Result<Matrix<T>>
+    where T: Parameter + ::std::ops::Mul<T, Output=T> + ::std::ops::Add<T, Output=T>
+{
+    mul_helper(column, row, column.len(), 1, row.len())
 }
 
-fn mul_helper<T: Parameter>(a: &ocl::Buffer<T>, b: &ocl::Buffer<T>, a_row_count: usize, a_col_count: usize, b_col_count: usize) -> Matrix<T> {
-    let mut kernels = get_kernels::<T>(T::type_to_str());
-    let queue = kernels.queue.clone();
+fn mul_helper<T>(a: &Vector<T>, b: &Vector<T>, a_row_count: usize, a_col_count: usize, b_col_count: usize) -> Result<Matrix<T>>
+    where T: Parameter + ::std::ops::Mul<T, Output=T> + ::std::ops::Add<T, Output=T>
+{
+    a.context().check_same(b.context())?;
+    if let (Data::Host(a_data), Data::Host(b_data)) = (&a.data, &b.data) {
+        return Ok(Matrix {
+            data: Vector::from_host(native::mul_mat_mat(a_data, b_data, a_row_count, a_col_count, b_col_count), a.context()),
+            row_count: a_row_count,
+            col_count: b_col_count
+        });
+    }
+
+    let mut kernels = a.context().kernels::<T>()?;
     let kernel = &mut kernels.mul_mat_mat;
 
-    let mut res = unsafe{ Matrix::uninitialized_lock_free(
+    let mut res = unsafe{ Matrix::try_uninitialized_in(
         a_row_count,
         b_col_count,
-        queue
-    )};
-
-    kernel.set_arg_buf_named("C", Some(&mut res.data.data)).unwrap();
-    kernel.set_arg_buf_named("A", Some(a)).unwrap();
-    kernel.set_arg_buf_named("B", Some(b)).unwrap();
+        a.context()
+    )? };
 
-    kernel.set_arg_scl_named::<i32>("C_col_count", res.col_count as i32).unwrap();
-    kernel.set_arg_scl_named::<i32>("A_col_count", a_col_count as i32).unwrap();
+    kernel.set_arg_buf_named("C", Some(res.data.buf_mut()))?;
+    kernel.set_arg_buf_named("A", Some(a.buf()))?;
+    kernel.set_arg_buf_named("B", Some(b.buf()))?;
 
-    unsafe {
-        let mut event = ocl::Event::empty();
-        kernel.cmd().enew(&mut event).gws(res.get_col_count() * res.get_row_count()).enq().unwrap();
-        event.wait_for().unwrap();
-    }
+    kernel.set_arg_scl_named::<i32>("C_col_count", res.col_count as i32)?;
+    kernel.set_arg_scl_named::<i32>("A_col_count", a_col_count as i32)?;
 
-    res
-}
+    let len = res.get_col_count() * res.get_row_count();
+    let candidates = tune::elementwise_candidates(kernel, len)?;
+    let name = kernel.name();
 
-//Div by scalar
-impl<'a, 'b, T: Parameter + ::std::ops::Div<T, Output=T>> ::std::ops::Div<T> for &'a Matrix<T> {
-    type Output = Matrix<T>;
-    fn div(self, scalar: T) -> Matrix<T> {
-        Matrix {
-            data: &self.data / scalar,
-            row_count: self.row_count,
-            col_count: self.col_count
+    let event = tune::launch(a.context().cl_queue()?, &name, len, &candidates, |kp| unsafe {
+        let wait_list = Vector::wait_list(&[a, b], &res.data);
+        let mut event = ocl::Event::empty();
+        let cmd = kernel.cmd()
+            .queue(res.context().cl_queue()?)
+            .ewait(&wait_list)
+            .enew(&mut event)
+            .gws(kp.global_work_size);
+        if kp.work_group_size > 0 {
+            cmd.lws(kp.work_group_size).enq()?;
+        } else {
+            cmd.enq()?;
         }
-    }
+        Ok(event)
+    })?;
+    res.context().profile_kernel(kernel, &event);
+    Vector::add_read_event(&[a, b], &event);
+    res.data.set_event(event);
+
+    Ok(res)
 }
 
-impl<'a, 'b, T> ::std::cmp::PartialEq for Matrix<T>
+impl<T> ::std::cmp::PartialEq for Matrix<T>
     where T: Copy + ::std::cmp::PartialEq + Parameter
 {
     fn eq(&self, other: &Matrix<T>) -> bool {
-        self.row_count == other.row_count &&
-            self.col_count == other.col_count &&
-            self.data == other.data
+        self.try_eq(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -291,7 +798,7 @@ impl<T: Parameter + ::std::fmt::Debug> ::std::fmt::Debug for Matrix<T> {
         let v = self.data.to_vec();
         writeln!(f, "Mat{}x{} {{", self.row_count, self.col_count)?;
         for row in 0..self.row_count {
-            write!(f, "\t {:?}", v[row * self.col_count + 0])?;
+            write!(f, "\t {:?}", v[row * self.col_count])?;
             for col in 1..self.col_count {
                 write!(f, ", {:?}",v[row * self.col_count + col])?;
             }
@@ -306,7 +813,7 @@ impl<T: Parameter + ::std::fmt::Display> ::std::fmt::Display for Matrix<T> {
         let v = self.data.to_vec();
         writeln!(f, "Mat{}x{} {{", self.row_count, self.col_count)?;
         for row in 0..self.row_count {
-            write!(f, "\t {}", v[row * self.col_count + 0])?;
+            write!(f, "\t {}", v[row * self.col_count])?;
             for col in 1..self.col_count {
                 write!(f, ", {}",v[row * self.col_count + col])?;
             }
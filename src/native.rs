@@ -0,0 +1,86 @@
+//! Pure Rust implementations of the kernels in kernels.cl
+//!
+//! These are used instead of OpenCL when no OpenCL device can be found or when the crate is
+//! built with the `no_opencl` feature.
+
+use ::std::ops::{ Add, Mul };
+use ::std::iter::Sum;
+
+/// C[i] = f(A[i], B[i])
+pub fn zip<T: Copy, F: Fn(T, T) -> T>(a: &[T], b: &[T], f: F) -> Vec<T> {
+    a.iter().zip(b.iter()).map(|(&a, &b)| f(a, b)).collect()
+}
+
+/// f(&mut C[i], B[i])
+pub fn zip_assign<T: Copy, F: Fn(&mut T, T)>(c: &mut [T], b: &[T], f: F) {
+    for (c, &b) in c.iter_mut().zip(b.iter()) {
+        f(c, b);
+    }
+}
+
+/// C[i] = f(A[i])
+pub fn map<T: Copy, F: Fn(T) -> T>(a: &[T], f: F) -> Vec<T> {
+    a.iter().map(|&a| f(a)).collect()
+}
+
+/// f(&mut C[i])
+pub fn map_assign<T, F: Fn(&mut T)>(c: &mut [T], f: F) {
+    for c in c.iter_mut() {
+        f(c);
+    }
+}
+
+pub fn eq_vec<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+    a == b
+}
+
+/// Calculate the sum of all the elements in the vector
+pub fn sum_vec<T: Copy + Sum<T>>(data: &[T]) -> T {
+    data.iter().cloned().sum()
+}
+
+/// Calculate the dot product of two vectors
+pub fn dot_vec_vec<T: Copy + Mul<T, Output=T> + Sum<T>>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b.iter()).map(|(&a, &b)| a * b).sum()
+}
+
+fn dot_product<T>(a: &[T], stride_a: usize, b: &[T], stride_b: usize, count: usize) -> T
+    where T: Copy + Default + Add<T, Output=T> + Mul<T, Output=T>
+{
+    let mut res = T::default();
+    for j in 0..count {
+        res = res + a[j * stride_a] * b[j * stride_b];
+    }
+    res
+}
+
+pub fn mul_vec_mat<T>(a: &[T], b: &[T], b_col_count: usize) -> Vec<T>
+    where T: Copy + Default + Add<T, Output=T> + Mul<T, Output=T>
+{
+    (0..b_col_count)
+        .map(|col| dot_product(a, 1, &b[col..], b_col_count, a.len()))
+        .collect()
+}
+
+pub fn mul_vec_transpose_mat<T>(a: &[T], b: &[T], b_col_count: usize) -> Vec<T>
+    where T: Copy + Default + Add<T, Output=T> + Mul<T, Output=T>
+{
+    (0..(b.len() / b_col_count))
+        .map(|row| dot_product(a, 1, &b[row * b_col_count..], 1, a.len()))
+        .collect()
+}
+
+pub fn mul_mat_mat<T>(a: &[T], b: &[T], a_row_count: usize, a_col_count: usize, b_col_count: usize) -> Vec<T>
+    where T: Copy + Default + Add<T, Output=T> + Mul<T, Output=T>
+{
+    //C.row_count = A.row_count
+    //C.col_count = B.col_count
+    //A.col_count = B.row_count
+    let mut c = Vec::with_capacity(a_row_count * b_col_count);
+    for row in 0..a_row_count {
+        for col in 0..b_col_count {
+            c.push(dot_product(&a[a_col_count * row..], 1, &b[col..], b_col_count, a_col_count));
+        }
+    }
+    c
+}
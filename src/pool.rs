@@ -0,0 +1,140 @@
+//! Pool of device buffers for reuse
+//!
+//! Buffers of dropped Vectors are kept per context, bucketed by element type and size class, and
+//! handed out again for new vectors of the same type whose length falls into the same class.
+//! Buffers are allocated with the length of their size class, so a vector may use only a prefix
+//! of its buffer. Every buffer is kept with the events that commands of its new vector have to
+//! wait for, see Context::recycle_buffer().
+//!
+//! Only buffers the library allocated itself and never handed out are kept. OpenCL reference
+//! counts can not tell, idle kernels and events hold references to buffers as well.
+
+extern crate ocl;
+
+use std::any::{ Any, TypeId };
+use std::collections::{ HashMap, HashSet };
+use std::mem::size_of;
+
+use traits::Parameter;
+
+/// Default limit of the bytes kept in the pool of a context
+pub(crate) const DEFAULT_POOL_LIMIT: usize = 256 * 1024 * 1024;
+
+/// Buffer<T>, the events to wait for and its address
+type Pooled = (Box<dyn Any + Send>, ocl::EventList, usize);
+
+pub(crate) struct BufferPool {
+    /// Buffers by TypeId of T and size class
+    buffers: HashMap<(TypeId, usize), Vec<Pooled>>,
+
+    /// Bytes of all buffers in the pool
+    size: usize,
+
+    /// Buffers that would make size exceed limit are released instead of being kept
+    limit: usize,
+
+    /// Buffers allocated for vectors of the context that nothing else refers to, by address
+    owned: HashSet<usize>,
+}
+
+impl Default for BufferPool {
+    fn default() -> BufferPool {
+        BufferPool {
+            buffers: HashMap::new(),
+            size: 0,
+            limit: DEFAULT_POOL_LIMIT,
+            owned: HashSet::new(),
+        }
+    }
+}
+
+impl BufferPool {
+    /// Take a buffer of size_class(len) elements out of the pool
+    pub(crate) fn take<T: Parameter>(&mut self, len: usize) -> Option<(ocl::Buffer<T>, ocl::EventList)> {
+        let class = size_class(len);
+        let buffer = self.buffers.get_mut(&(TypeId::of::<T>(), class))
+            .and_then(|buffers| buffers.pop())
+            .map(|(b, events, _)| (*b.downcast::<ocl::Buffer<T>>().unwrap(), events));
+
+        if buffer.is_some() {
+            self.size -= class * size_of::<T>();
+        }
+        buffer
+    }
+
+    /// Record that buffer was allocated for a vector and may be kept once the vector is dropped
+    pub(crate) fn own<T: Parameter>(&mut self, buffer: &ocl::Buffer<T>) {
+        self.owned.insert(address(buffer));
+    }
+
+    /// Forget buffer, e.g. because it was handed out to other code which may keep a clone of it
+    ///
+    /// The buffer a sub-buffer was created from is forgotten as well, the sub-buffer of a view
+    /// aliases its memory.
+    pub(crate) fn disown<T: Parameter>(&mut self, buffer: &ocl::Buffer<T>) {
+        use ocl::enums::{ MemInfo, MemInfoResult };
+
+        self.owned.remove(&address(buffer));
+        if let MemInfoResult::AssociatedMemobject(Some(parent)) = buffer.mem_info(MemInfo::AssociatedMemobject) {
+            self.owned.remove(&(parent.as_ptr() as usize));
+        }
+    }
+
+    /// Keep buffer for reuse unless that would exceed the limit, commands using it later have to
+    /// wait for events
+    ///
+    /// Buffers not owned by the pool, e.g. ones wrapped by Vector::from_buffer(), are not kept
+    /// since reusing them would overwrite data still in use.
+    pub(crate) fn put<T: Parameter>(&mut self, buffer: ocl::Buffer<T>, events: ocl::EventList) {
+        let bytes = buffer.len() * size_of::<T>();
+        if !self.owned.contains(&address(&buffer)) {
+            return;
+        }
+        if bytes == 0 || self.size + bytes > self.limit {
+            self.disown(&buffer);
+            return;
+        }
+
+        self.size += bytes;
+        let address = address(&buffer);
+        self.buffers.entry((TypeId::of::<T>(), buffer.len()))
+            .or_default()
+            .push((Box::new(buffer), events, address));
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Set the limit, releasing all buffers if the pool does not fit
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.size > limit {
+            self.trim();
+        }
+    }
+
+    /// Release all buffers in the pool
+    pub(crate) fn trim(&mut self) {
+        for (_, buffers) in self.buffers.drain() {
+            for (_, _, address) in buffers {
+                self.owned.remove(&address);
+            }
+        }
+        self.size = 0;
+    }
+}
+
+/// Number of elements allocated for a vector of len elements
+///
+/// Lengths are rounded up to a multiple of a sixteenth of the next power of two, wasting at most
+/// an eighth of a buffer while letting vectors of slightly different lengths share buffers.
+pub(crate) fn size_class(len: usize) -> usize {
+    let step = (len.next_power_of_two() / 16).max(1);
+    (len + step - 1) & !(step - 1)
+}
+
+/// Address of the OpenCL memory object of buffer, unique while the buffer is alive
+fn address<T: Parameter>(buffer: &ocl::Buffer<T>) -> usize {
+    buffer.core().as_ptr() as usize
+}
@@ -0,0 +1,173 @@
+//! Timing statistics of kernels and transfers
+//!
+//! Profiling is opt-in, enable it with set_profiling() or the LINEAR_ALGEBRA_PROFILE environment
+//! variable before creating a context. The device times of every kernel and transfer run in
+//! that context are then recorded from their events and can be read with
+//! Context::profile_report().
+
+extern crate ocl;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use context::Context;
+
+/// Timing statistics of one kernel or transfer for one element type
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileEntry {
+    /// Kernel name without the type prefix, e.g. add_vec_vec, or read/write for transfers
+    pub name: String,
+    /// OpenCL name of the element type, e.g. float
+    pub ty: String,
+    pub count: u64,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl ProfileEntry {
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::from_secs(0)
+        } else {
+            self.total / self.count as u32
+        }
+    }
+}
+
+/// Timing statistics of a context grouped by kernel name and element type
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileReport {
+    /// Entries sorted by total time, largest first
+    pub entries: Vec<ProfileEntry>,
+}
+
+impl ProfileReport {
+    /// Sum of the time of all entries
+    pub fn total(&self) -> Duration {
+        self.entries.iter().fold(Duration::from_secs(0), |sum, e| sum + e.total)
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<32} {:<8} {:>8} {:>12} {:>12} {:>12} {:>12}",
+            "name", "type", "count", "total us", "mean us", "min us", "max us")?;
+        for e in &self.entries {
+            writeln!(f, "{:<32} {:<8} {:>8} {:>12} {:>12} {:>12} {:>12}",
+                e.name, e.ty, e.count, micros(e.total), micros(e.mean()), micros(e.min), micros(e.max))?;
+        }
+        writeln!(f, "total: {} us", micros(self.total()))
+    }
+}
+
+fn micros(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000 + d.subsec_nanos() as u64 / 1_000
+}
+
+/// Records events of a context, internal use only
+///
+/// Events are kept until they complete since their times can not be queried before.
+#[derive(Default)]
+pub(crate) struct Profiler {
+    pending: Vec<(String, String, ocl::Event)>,
+    entries: HashMap<(String, String), ProfileEntry>,
+}
+
+impl Profiler {
+    /// Record the command of event, name is split into type and kernel name at the first '_'
+    /// if ty is None
+    pub(crate) fn record(&mut self, name: &str, ty: Option<&str>, event: &ocl::Event) {
+        let (name, ty) = match ty {
+            Some(ty) => (name.to_owned(), ty.to_owned()),
+            None => match name.find('_') {
+                Some(idx) => (name[idx + 1..].to_owned(), name[..idx].to_owned()),
+                None => (name.to_owned(), String::new()),
+            },
+        };
+        self.pending.push((name, ty, event.clone()));
+        self.collect(false);
+    }
+
+    /// Move the times of completed events into the statistics, waits for all events if wait
+    /// is true. Events whose times can not be queried are dropped.
+    pub(crate) fn collect(&mut self, wait: bool) {
+        let pending = ::std::mem::take(&mut self.pending);
+        for (name, ty, event) in pending {
+            let complete = if wait {
+                event.wait_for().is_ok()
+            } else {
+                ocl::core::event_is_complete(&event).unwrap_or(true)
+            };
+
+            if !complete {
+                self.pending.push((name, ty, event));
+            } else if let Some(time) = event_time(&event) {
+                self.add(name, ty, time);
+            }
+        }
+    }
+
+    pub(crate) fn add(&mut self, name: String, ty: String, time: Duration) {
+        let entry = self.entries.entry((name.clone(), ty.clone())).or_insert(ProfileEntry {
+            name,
+            ty,
+            count: 0,
+            total: Duration::from_secs(0),
+            min: time,
+            max: time,
+        });
+        entry.count += 1;
+        entry.total += time;
+        entry.min = entry.min.min(time);
+        entry.max = entry.max.max(time);
+    }
+
+    pub(crate) fn report(&mut self) -> ProfileReport {
+        self.collect(true);
+
+        let mut entries: Vec<ProfileEntry> = self.entries.values().cloned().collect();
+        entries.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        ProfileReport { entries }
+    }
+}
+
+/// Time between start and end of the command of a completed event
+fn event_time(event: &ocl::Event) -> Option<Duration> {
+    use ocl::enums::{ ProfilingInfo, ProfilingInfoResult };
+
+    let start = match event.profiling_info(ProfilingInfo::Start) {
+        ProfilingInfoResult::Start(t) => t,
+        _ => return None,
+    };
+    let end = match event.profiling_info(ProfilingInfo::End) {
+        ProfilingInfoResult::End(t) => t,
+        _ => return None,
+    };
+
+    let nanos = end.saturating_sub(start);
+    Some(Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32))
+}
+
+/// Prints the profile report of a context to stderr when dropped, see dump_at_exit()
+pub struct DumpGuard(Context);
+
+impl Drop for DumpGuard {
+    fn drop(&mut self) {
+        if let Some(report) = self.0.profile_report() {
+            eprintln!("{}", report);
+        }
+    }
+}
+
+/// Print the profile report of context to stderr when the returned guard is dropped
+///
+/// Keep the guard alive until the end of main to get a report at exit:
+///
+/// ```ignore
+/// let _profile = profile::dump_at_exit(&Context::default());
+/// ```
+pub fn dump_at_exit(context: &Context) -> DumpGuard {
+    DumpGuard(context.clone())
+}
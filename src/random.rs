@@ -0,0 +1,201 @@
+//! Counter-based random number generation
+//!
+//! Vectors and matrices are filled on the device with the Philox4x32-10 generator. Every
+//! element is computed from the seed, the element index and the number of fills the Rng has
+//! done so far, so the same seed gives the same random bits on every device and on the native
+//! backend:
+//!
+//! ```ignore
+//! let mut rng = Rng::new(42);
+//! let mut weights = Matrix::<f32>::zeros(256, 128);
+//! weights.fill_normal(&mut rng, 0.0, 0.01);
+//! let mut mask = Vector::<u8>::zeros(1024);
+//! mask.fill_bernoulli(&mut rng, 0.5);
+//! ```
+//!
+//! Only integer, uniform and bernoulli fills give the same data bit for bit everywhere. Normal
+//! fills use log(), sqrt() and cos(), whose results may differ in the last bits between devices
+//! and the host.
+
+use std::f64;
+
+use error::{ Error, Result };
+use traits::Parameter;
+
+const PHILOX_M0: u32 = 0xD251_1F53;
+const PHILOX_M1: u32 = 0xCD9E_8D57;
+const PHILOX_W0: u32 = 0x9E37_79B9;
+const PHILOX_W1: u32 = 0xBB67_AE85;
+
+/// Seeded generator, every fill advances it to a new stream of numbers
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rng {
+    seed: u64,
+    stream: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { seed, stream: 0 }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of fills done with this generator
+    pub fn stream(&self) -> u64 {
+        self.stream
+    }
+
+    /// Continue at a given stream, e.g. to reproduce a single fill of an earlier run
+    pub fn set_stream(&mut self, stream: u64) {
+        self.stream = stream;
+    }
+
+    /// Returns the stream for the next fill
+    pub(crate) fn next_stream(&mut self) -> u64 {
+        let stream = self.stream;
+        self.stream = self.stream.wrapping_add(1);
+        stream
+    }
+}
+
+/// Element types which can be filled with uniform random numbers
+///
+/// Floats are uniform between low and high, integers uniform in the range low..high.
+pub trait Random: Parameter {
+    /// Value of the range argument of the uniform kernel, fails for empty integer ranges
+    #[doc(hidden)]
+    fn uniform_range(low: Self, high: Self) -> Result<u64>;
+
+    #[doc(hidden)]
+    fn sample_uniform(bits: [u32; 4], low: Self, high: Self, range: u64) -> Self;
+}
+
+/// Element types which can be filled with normal distributed random numbers
+pub trait RandomNormal: Random {
+    #[doc(hidden)]
+    fn sample_normal(bits: [u32; 4], mean: Self, std_dev: Self) -> Self;
+}
+
+macro_rules! impl_random_int {
+    ($( $ty:ident ),+) => {
+        $( impl Random for $ty {
+            fn uniform_range(low: Self, high: Self) -> Result<u64> {
+                if low >= high {
+                    return Err(Error::InvalidArgument(format!("Empty range {:?}..{:?} for random numbers", low, high)));
+                }
+                Ok((high as i128 - low as i128) as u64)
+            }
+
+            fn sample_uniform(bits: [u32; 4], low: Self, _high: Self, range: u64) -> Self {
+                let offset = (u128::from(bits64(bits[0], bits[1])) * u128::from(range)) >> 64;
+                (low as u64).wrapping_add(offset as u64) as $ty
+            }
+        } )+
+    }
+}
+
+impl_random_int!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+
+impl Random for f32 {
+    fn uniform_range(_low: Self, _high: Self) -> Result<u64> {
+        Ok(0)
+    }
+
+    fn sample_uniform(bits: [u32; 4], low: Self, high: Self, _range: u64) -> Self {
+        low + unit_f32(bits[0]) * (high - low)
+    }
+}
+
+impl Random for f64 {
+    fn uniform_range(_low: Self, _high: Self) -> Result<u64> {
+        Ok(0)
+    }
+
+    fn sample_uniform(bits: [u32; 4], low: Self, high: Self, _range: u64) -> Self {
+        low + unit_f64(bits[0], bits[1]) * (high - low)
+    }
+}
+
+impl RandomNormal for f32 {
+    fn sample_normal(bits: [u32; 4], mean: Self, std_dev: Self) -> Self {
+        // Box-Muller, u1 is in (0, 1] to keep the log finite
+        let u1 = ((bits[0] >> 8) + 1) as f32 * (1.0 / 16_777_216.0);
+        let u2 = unit_f32(bits[1]);
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * ::std::f32::consts::PI * u2).cos();
+        mean + std_dev * z
+    }
+}
+
+impl RandomNormal for f64 {
+    fn sample_normal(bits: [u32; 4], mean: Self, std_dev: Self) -> Self {
+        let u1 = ((bits64(bits[0], bits[1]) >> 11) + 1) as f64 * (1.0 / 9_007_199_254_740_992.0);
+        let u2 = unit_f64(bits[2], bits[3]);
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * f64::consts::PI * u2).cos();
+        mean + std_dev * z
+    }
+}
+
+/// Uniform in [0, 1) from the upper 24 bits
+fn unit_f32(x: u32) -> f32 {
+    (x >> 8) as f32 * (1.0 / 16_777_216.0)
+}
+
+/// Uniform in [0, 1) from the upper 53 bits
+fn unit_f64(hi: u32, lo: u32) -> f64 {
+    (bits64(hi, lo) >> 11) as f64 * (1.0 / 9_007_199_254_740_992.0)
+}
+
+fn bits64(hi: u32, lo: u32) -> u64 {
+    (u64::from(hi) << 32) | u64::from(lo)
+}
+
+/// Elements are one if the first word of their random bits is below the threshold
+pub(crate) fn bernoulli_threshold(p: f64) -> Result<u64> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(Error::InvalidArgument(format!("Probability {} is not in [0, 1]", p)));
+    }
+    Ok((p * 4_294_967_296.0) as u64)
+}
+
+/// Philox4x32-10 block of the element at index, same as {T}_philox in kernels.cl
+pub(crate) fn philox(index: u64, stream: u64, seed: u64) -> [u32; 4] {
+    let mut ctr = [index as u32, (index >> 32) as u32, stream as u32, (stream >> 32) as u32];
+    let mut key = [seed as u32, (seed >> 32) as u32];
+
+    for _ in 0..10 {
+        let p0 = u64::from(PHILOX_M0) * u64::from(ctr[0]);
+        let p1 = u64::from(PHILOX_M1) * u64::from(ctr[2]);
+        ctr = [
+            (p1 >> 32) as u32 ^ ctr[1] ^ key[0],
+            p1 as u32,
+            (p0 >> 32) as u32 ^ ctr[3] ^ key[1],
+            p0 as u32,
+        ];
+        key[0] = key[0].wrapping_add(PHILOX_W0);
+        key[1] = key[1].wrapping_add(PHILOX_W1);
+    }
+    ctr
+}
+
+//Host versions of the kernels in kernels.cl
+
+pub(crate) fn fill_uniform<T: Random>(c: &mut [T], seed: u64, stream: u64, low: T, high: T, range: u64) {
+    for (idx, x) in c.iter_mut().enumerate() {
+        *x = T::sample_uniform(philox(idx as u64, stream, seed), low, high, range);
+    }
+}
+
+pub(crate) fn fill_normal<T: RandomNormal>(c: &mut [T], seed: u64, stream: u64, mean: T, std_dev: T) {
+    for (idx, x) in c.iter_mut().enumerate() {
+        *x = T::sample_normal(philox(idx as u64, stream, seed), mean, std_dev);
+    }
+}
+
+pub(crate) fn fill_bernoulli<T: Parameter>(c: &mut [T], seed: u64, stream: u64, threshold: u64) {
+    for (idx, x) in c.iter_mut().enumerate() {
+        *x = if u64::from(philox(idx as u64, stream, seed)[0]) < threshold { T::ONE } else { T::ZERO };
+    }
+}
@@ -20,6 +20,30 @@ fn f32_vec_mat_mul() {
     assert_eq!(p, b);
 }
 
+#[test]
+fn f64_vec_mat_mul() {
+    use vector::*;
+    use matrix::*;
+    use context::Context;
+
+    if !Context::default().supports_f64() {
+        return;
+    }
+
+    let a: Vector<f64> = Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+    let b: Vector<f64> = Vector::from_vec(vec![5.0, 4.0, 9.0]);
+    let m: Matrix<f64> = Matrix::from_vec(vec![
+        1.0, 0.0, 0.0,
+        0.0, 2.0, 0.0,
+        0.0, 0.0, 3.0,
+        1.0, 0.0, 0.0
+    ], 4, 3);
+
+    let p: Vector<f64> = &a * &m;
+    assert_eq!(p, b);
+    assert_eq!((&p + &b).eval().to_vec(), vec![10.0, 8.0, 18.0]);
+}
+
 #[test]
 fn vec_eq() {
 
@@ -200,7 +224,7 @@ fn vec_mul_scl() {
     let r: Vector<TestType> = Vector::from_vec(vec![2, 4, 6, 8]);
 
 
-    let p: Vector<TestType> = &a * 2;
+    let p: Vector<TestType> = (&a * 2).eval();
 
     assert_eq!(r, p);
 }
@@ -283,19 +307,962 @@ fn vec_clone() {
     assert_ne!(a, b);
 }
 
+#[test]
+fn native_mat_mat_mul() {
+    use native;
+
+    let a: Vec<TestType> = vec![
+        1, 2, 3,
+        4, 5, 6,
+    ];
+
+    let b: Vec<TestType> = vec![
+        7, 8,
+        9, 10,
+        11, 12
+    ];
+
+    assert_eq!(native::mul_mat_mat(&a, &b, 2, 3, 2), vec![58, 64, 139, 154]);
+}
+
+#[test]
+fn native_vec_mat_mul() {
+    use native;
+
+    let a: Vec<TestType> = vec![1, 2, 3, 4];
+    let m: Vec<TestType> = vec![
+        1, 0, 0,
+        0, 2, 0,
+        0, 0, 3,
+        1, 0, 0
+    ];
+    let m_t: Vec<TestType> = vec![
+        1, 0, 0, 1,
+        0, 2, 0, 0,
+        0, 0, 3, 0
+    ];
+
+    assert_eq!(native::mul_vec_mat(&a, &m, 3), vec![5, 4, 9]);
+    assert_eq!(native::mul_vec_transpose_mat(&a, &m_t, 4), vec![5, 4, 9]);
+}
+
+#[test]
+fn context_vec_add() {
+    use vector::*;
+    use context::Context;
+
+    let ctx = Context::native();
+
+    let a: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &ctx);
+    let r: Vector<TestType> = Vector::from_vec_in(vec![2, 4, 6, 8], &ctx);
+
+    let s = (&a + &a).eval();
+
+    assert!(s.context() == &ctx);
+    assert_eq!(r, s);
+}
+
+#[test]
+#[should_panic]
+fn context_mismatch() {
+    use vector::*;
+    use context::Context;
+
+    let a: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &Context::native());
+    let b: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &Context::native());
+
+    let _ = (&a + &b).eval();
+}
+
+#[test]
+fn try_shape_mismatch() {
+    use vector::*;
+    use matrix::*;
+    use error::Error;
+    use context::Context;
+
+    let ctx = Context::native();
+
+    let a: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &ctx);
+    let b: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3], &ctx);
+
+    match a.try_add(&b) {
+        Err(Error::ShapeMismatch(_)) => {},
+        r => panic!("Expected ShapeMismatch, got {:?}", r),
+    }
+
+    let m: Matrix<TestType> = Matrix::from_vec_in(vec![1, 2, 3, 4, 5, 6], 2, 3, &ctx);
+    assert!(m.try_mul(&m).is_err());
+    assert!(Matrix::<TestType>::try_from_vec_in(vec![1, 2, 3], 2, 2, &ctx).is_err());
+
+    let other: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &Context::native());
+    match a.try_add(&other) {
+        Err(Error::ContextMismatch) => {},
+        r => panic!("Expected ContextMismatch, got {:?}", r),
+    }
+}
+
+#[test]
+fn try_open_wrong_elem_size() {
+    use vector::*;
+    use error::Error;
+    let path = "try_open_wrong_elem_size.tmp";
+
+    let a: Vector<u8> = Vector::from_vec(vec![1, 2, 3, 4]);
+    a.save(path).unwrap();
+
+    let b = unsafe{ Vector::<TestType>::try_open(path) };
+    let c = unsafe{ Vector::<TestType>::open(path) };
+    ::std::fs::remove_file(path).unwrap();
+
+    assert_eq!(c.unwrap_err().kind(), ::std::io::ErrorKind::Other);
+    match b {
+        Err(Error::Format(_)) => {},
+        r => panic!("Expected Format error, got {:?}", r),
+    }
+
+    match unsafe{ Vector::<TestType>::try_open(path) } {
+        Err(Error::Io(_)) => {},
+        r => panic!("Expected Io error, got {:?}", r),
+    }
+}
+
+#[test]
+fn chained_ops_finish() {
+    use vector::*;
+    use matrix::*;
+
+    let a: Vector<TestType> = Vector::from_vec(vec![1, 2, 3, 4]);
+    let b: Vector<TestType> = Vector::from_vec(vec![4, 3, 2, 1]);
+
+    let mut c = (&(&a + &b) * 2).eval();
+    c -= &a;
+    c.finish();
+
+    let m: Matrix<TestType> = Matrix::from_vec(vec![1, 0, 0, 1], 2, 2);
+    let p = &m * &m;
+    p.finish();
+
+    assert_eq!(c.to_vec(), vec![9, 8, 7, 6]);
+    assert_eq!(p, m);
+}
+
+#[test]
+fn lazy_expr() {
+    use vector::*;
+
+    let a: Vector<TestType> = Vector::from_vec(vec![1, 2, 3, 4]);
+    let b: Vector<TestType> = Vector::from_vec(vec![4, 3, 2, 1]);
+
+    let c = ((a.lazy() + &b) * 2 - &a).eval();
+    let r = a.try_add(&b).unwrap().try_mul_scl(2).unwrap().try_sub(&a).unwrap();
+
+    assert_eq!(c, r);
+    assert_eq!((a.lazy() * &a / &a).eval(), a);
+    assert_eq!(&(&(&a + &b) * 2) - &a, r);
+    assert_eq!(Vector::from(&a + &(&b * 2)).to_vec(), vec![9, 8, 7, 6]);
+
+    let short: Vector<TestType> = Vector::from_vec(vec![1, 2]);
+    assert!((a.lazy() + &short).try_eval().is_err());
+    assert!((&a + &short).try_eval().is_err());
+}
+
+#[test]
+fn lazy_matrix_expr() {
+    use matrix::*;
+    use error::Error;
+
+    let a: Matrix<TestType> = Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 2, 3);
+    let b: Matrix<TestType> = Matrix::from_vec(vec![6, 5, 4, 3, 2, 1], 2, 3);
+
+    let c = &(&a + &b) * 3 - &a;
+    assert_eq!(c, Matrix::from_vec(vec![20, 19, 18, 17, 16, 15], 2, 3));
+    assert_eq!(Matrix::from(&a.lazy() / 1), a);
+
+    let t: Matrix<TestType> = Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 3, 2);
+    match (&a + &t).try_eval() {
+        Err(Error::ShapeMismatch(_)) => (),
+        r => panic!("expected Error::ShapeMismatch, got {:?}", r),
+    }
+}
+
+#[test]
+fn fused_kernel_src() {
+    use expr::fused_src;
+
+    let src = fused_src("((A0[i] + A1[i]) * S0)", 2, 1).replace("{T}", "int");
+    assert!(src.contains("kernel void int_fused(global int* C, global int* A0, global int* A1, int S0, ulong col_count)"));
+    assert!(src.contains("C[i] = ((A0[i] + A1[i]) * S0);"));
+}
+
+#[test]
+fn kernel_src_templating() {
+    let src = ::template_src(&vec!["int", "float"], include_str!("extra_kernels.cl"));
+
+    assert!(src.contains("#define IS_INT"));
+    assert!(src.contains("kernel void int_test_kernel(global int* a, global int* b)"));
+    assert!(src.contains("float float_test_func(float x)"));
+    assert!(!src.contains("{T}"));
+}
+
+#[test]
+fn kernel_src_single_type() {
+    let src = ::template_src(&vec!["float"], include_str!("kernels.cl"));
+
+    assert!(src.contains("float_add_vec_vec"));
+    assert!(!src.contains("int_add_vec_vec"));
+    assert!(!src.contains("cl_khr_fp64"));
+}
+
+#[test]
+fn kernel_src_double() {
+    let src = ::template_src(&vec!["double"], include_str!("kernels.cl"));
+
+    assert!(src.contains("double_add_vec_vec"));
+    assert!(src.contains("#pragma OPENCL EXTENSION cl_khr_fp64 : enable"));
+    assert!(src.contains("#pragma OPENCL EXTENSION cl_amd_fp64 : enable"));
+}
+
+#[test]
+fn add_kernel_src_native() {
+    use context::Context;
+    use error::Error;
+
+    match Context::native().add_kernel_src(include_str!("extra_kernels.cl")) {
+        Err(Error::NoDevice(_)) => {},
+        r => panic!("Expected NoDevice, got {:?}", r),
+    }
+}
+
+#[test]
+fn tuning_table_format() {
+    use tune::{ parse_table, format_table };
+    use KernelParams;
+
+    let content = "0123456789abcdef:float_sum_vec:10 64 32 2048\n\
+                   0123456789abcdef:float_mul_mat_mat:4 0 0 16\n\
+                   malformed line\n";
+    let table = parse_table(content);
+
+    assert_eq!(table.len(), 2);
+    assert_eq!(table["0123456789abcdef:float_sum_vec:10"],
+        KernelParams { work_group_size: 64, work_group_count: 32, global_work_size: 2048 });
+    assert_eq!(parse_table(&format_table(&table)), table);
+}
+
+#[test]
+fn tuned_length_in_same_bucket() {
+    use tune::tuned_candidate;
+    use vector::Vector;
+    use matrix::Matrix;
+    use KernelParams;
+
+    // Tuned for 1024 elements, launched for 1000 of the same bucket
+    let tuned = KernelParams { work_group_size: 64, work_group_count: 16, global_work_size: 1024 };
+    let elementwise = [
+        KernelParams { work_group_size: 0, work_group_count: 0, global_work_size: 1000 },
+        KernelParams { work_group_size: 8, work_group_count: 125, global_work_size: 1000 },
+    ];
+    assert_eq!(tuned_candidate(&elementwise, tuned), elementwise[0]);
+
+    let reduction = [
+        KernelParams { work_group_size: 64, work_group_count: 16, global_work_size: 1024 },
+        KernelParams { work_group_size: 64, work_group_count: 32, global_work_size: 2048 },
+    ];
+    assert_eq!(tuned_candidate(&reduction, tuned), reduction[0]);
+
+    // Tune with one length, then run another length of the same bucket
+    for &len in &[1024, 1000] {
+        let v: Vector<TestType> = Vector::from_vec((0..len).collect());
+        assert_eq!(v.sum(), (0..len).sum());
+    }
+    for &n in &[32, 30] {
+        let a: Matrix<TestType> = Matrix::from_vec(vec![1; n * n], n, n);
+        assert_eq!(a.try_mul(&a).unwrap(), Matrix::from_vec(vec![n as TestType; n * n], n, n));
+    }
+}
+
+#[test]
+fn profile_report() {
+    use profile::Profiler;
+    use std::time::Duration;
+
+    let mut profiler = Profiler::default();
+    profiler.add("add_vec_vec".to_owned(), "float".to_owned(), Duration::from_millis(1));
+    profiler.add("add_vec_vec".to_owned(), "float".to_owned(), Duration::from_millis(3));
+    profiler.add("read".to_owned(), "float".to_owned(), Duration::from_millis(1));
+
+    let report = profiler.report();
+    assert_eq!(report.entries.len(), 2);
+
+    let add = &report.entries[0];
+    assert_eq!((add.name.as_str(), add.ty.as_str(), add.count), ("add_vec_vec", "float", 2));
+    assert_eq!(add.min, Duration::from_millis(1));
+    assert_eq!(add.max, Duration::from_millis(3));
+    assert_eq!(add.mean(), Duration::from_millis(2));
+    assert_eq!(report.total(), Duration::from_millis(5));
+    assert!(report.to_string().contains("add_vec_vec"));
+}
+
+#[test]
+fn buffer_pool_native() {
+    use vector::Vector;
+    use context::Context;
+
+    let context = Context::native();
+    {
+        let a: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3, 4], &context);
+        let b = (&a + &a).eval();
+        assert_eq!(b.to_vec(), vec![2, 4, 6, 8]);
+    }
+
+    // Host vectors are not pooled
+    assert_eq!(context.pool_size(), 0);
+    context.set_pool_limit(0);
+    context.trim_pool();
+    assert_eq!(context.pool_size(), 0);
+}
+
+#[test]
+fn pool_size_classes() {
+    use pool::size_class;
+
+    assert_eq!(size_class(0), 0);
+    assert_eq!(size_class(7), 7);
+    assert_eq!(size_class(64), 64);
+    assert_eq!(size_class(62), 64);
+    assert_eq!(size_class(1000), 1024);
+    assert_eq!(size_class(1025), 1152);
+    assert!((1..5000).all(|n| size_class(n) >= n && size_class(n) - n <= n / 8));
+}
+
+#[test]
+fn buffer_pool_reuse() {
+    use vector::Vector;
+    use context::Context;
+    use std::mem::size_of;
+
+    let default = Context::default();
+    if default.is_native() {
+        return;
+    }
+
+    // A pool of its own, the default context is shared with the other tests
+    let context = Context::from_queue(default.queue().unwrap().clone()).unwrap();
+    let bytes = 64 * size_of::<TestType>();
+    {
+        let a: Vector<TestType> = Vector::from_vec_in(vec![1; 64], &context);
+        let b = (&a * 2).eval();
+        let mut c = a.clone();
+        c += &b;
+        // Idle kernels unbind their arguments, nothing else keeps the buffers alive
+        assert_eq!(c.sum(), 3 * 64);
+    }
+    context.finish().unwrap();
+
+    let pooled = context.pool_size();
+    assert!(pooled >= 3 * bytes, "pooled {} bytes", pooled);
+
+    let v: Vec<Vector<TestType>> = (0..3).map(|_| Vector::zeros_in(64, &context)).collect();
+    assert_eq!(context.pool_size(), pooled - 3 * bytes);
+    assert!(v.iter().all(|v| v.to_vec() == vec![0; 64]));
+
+    // Lengths of the same size class share buffers
+    drop(v);
+    let pooled = context.pool_size();
+    let short: Vector<TestType> = Vector::from_vec_in(vec![2; 62], &context);
+    assert_eq!(context.pool_size(), pooled - bytes);
+    assert_eq!(short.len(), 62);
+    assert_eq!((&short + 1).eval().to_vec(), vec![3; 62]);
+    assert_eq!(short.sum(), 124);
+    drop(short);
+    let v: Vec<Vector<TestType>> = (0..3).map(|_| Vector::zeros_in(64, &context)).collect();
+
+    // Buffers handed out, also through a view, are not reused
+    let mut w: Vector<TestType> = Vector::zeros_in(64, &context);
+    let pooled = context.pool_size();
+    for v in v {
+        unsafe {
+            v.view(0..32).get_buffer();
+        }
+    }
+    unsafe {
+        w.get_buf_mut();
+    }
+    drop(w);
+    assert_eq!(context.pool_size(), pooled);
+}
+
+#[test]
+fn device_list() {
+    use context::Context;
+    use vector::Vector;
+    use device::{ self, DeviceKind };
+
+    for d in device::list() {
+        assert!(!d.name.is_empty());
+        assert!(d.to_string().contains(&d.name));
+    }
+
+    let context = Context::native();
+    let v: Vector<TestType> = Vector::from_vec_in(vec![1, 2, 3], &context);
+    assert!(context.device().is_none());
+    assert!(v.device().is_none());
+    assert_eq!(DeviceKind::Gpu.to_string(), "GPU");
+}
+
+#[test]
+fn device_selector_parse() {
+    use device::{ DeviceSelector, DeviceKind };
+
+    let selector = DeviceSelector::parse("\
+        # Second NVIDIA GPU
+        platform = NVIDIA
+        type = GPU
+        index = 1
+    ").unwrap();
+
+    assert_eq!(selector, DeviceSelector {
+        platform: Some("NVIDIA".to_owned()),
+        device: None,
+        kind: Some(DeviceKind::Gpu),
+        index: Some(1),
+    });
+    assert!(!selector.is_empty());
+    assert!(DeviceSelector::parse("").unwrap().is_empty());
+
+    assert!(DeviceSelector::parse("type = tpu").is_err());
+    assert!(DeviceSelector::parse("index = first").is_err());
+    assert!(DeviceSelector::parse("colour = blue").is_err());
+    assert!(DeviceSelector::parse("gpu").is_err());
+}
+
+#[test]
+fn filled_constructors() {
+    use vector::Vector;
+    use matrix::Matrix;
+
+    assert_eq!(Vector::<TestType>::zeros(4).to_vec(), vec![0, 0, 0, 0]);
+    assert_eq!(Vector::<f32>::ones(3).to_vec(), vec![1.0, 1.0, 1.0]);
+    assert_eq!(Vector::filled(7 as TestType, 2).to_vec(), vec![7, 7]);
+    assert_eq!(Vector::new(5 as TestType, 3).to_vec(), vec![5, 5, 5]);
+    assert_eq!(Vector::<TestType>::zeros(0).len(), 0);
+
+    assert_eq!(Matrix::<TestType>::ones(2, 3), Matrix::from_vec(vec![1; 6], 2, 3));
+    assert_eq!(Matrix::<TestType>::zeros(3, 2), Matrix::from_vec(vec![0; 6], 3, 2));
+    assert_eq!(Matrix::<TestType>::identity(3), Matrix::from_vec(vec![
+        1, 0, 0,
+        0, 1, 0,
+        0, 0, 1
+    ], 3, 3));
+}
+
+#[test]
+fn slice_transfers() {
+    use vector::Vector;
+    use matrix::Matrix;
+
+    let mut v = Vector::from_slice(&[1 as TestType, 2, 3, 4, 5]);
+    let mut out = [0 as TestType; 5];
+    v.read_into(&mut out);
+    assert_eq!(out, [1, 2, 3, 4, 5]);
+
+    let mut part = [0 as TestType; 2];
+    v.read_range_into(2, &mut part);
+    assert_eq!(part, [3, 4]);
+
+    v.write_range_from(3, &[8, 9]);
+    assert_eq!(v.to_vec(), vec![1, 2, 3, 8, 9]);
+    v.write_from(&[5, 4, 3, 2, 1]);
+    assert_eq!(v.to_vec(), vec![5, 4, 3, 2, 1]);
+
+    assert!(v.try_read_into(&mut part).is_err());
+    assert!(v.try_read_range_into(4, &mut part).is_err());
+    assert!(v.try_write_range_from(usize::MAX, &[1]).is_err());
+
+    let mut m = Matrix::from_slice(&[1 as TestType, 2, 3, 4, 5, 6], 2, 3);
+    assert_eq!(m, Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 2, 3));
+    m.write_range_from(3, &[0, 0, 0]);
+    let mut row = [1 as TestType; 3];
+    m.read_range_into(3, &mut row);
+    assert_eq!(row, [0, 0, 0]);
+    assert!(Matrix::try_from_slice_in(&[1 as TestType, 2, 3], 2, 2, &::context::Context::default()).is_err());
+}
+
+#[test]
+fn async_transfers() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{ Context, Poll, RawWaker, RawWakerVTable, Waker };
+
+    let v = Vector::from_vec(vec![1 as TestType, 2, 3]);
+    assert_eq!(v.to_vec_async().wait().unwrap(), vec![1, 2, 3]);
+
+    let pending = v.read_into_async(vec![0; 3]);
+    let w = (&v + &v).eval();
+    assert_eq!(pending.wait().unwrap(), vec![1, 2, 3]);
+    assert_eq!(w.to_vec(), vec![2, 4, 6]);
+    assert!(v.try_read_into_async(vec![0; 2]).is_err());
+
+    let mut transfer = Matrix::from_vec(vec![1 as TestType, 2, 3, 4], 2, 2).to_vec_async();
+    // Waker::noop() requires Rust 1.85
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker { noop_raw_waker() }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(::std::ptr::null(), &VTABLE)
+    }
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let res = loop {
+        if let Poll::Ready(res) = Pin::new(&mut transfer).poll(&mut cx) {
+            break res;
+        }
+    };
+    assert_eq!(res.unwrap(), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn streams() {
+    use vector::Vector;
+    use context::Context;
+
+    let context = Context::default();
+    let stream = context.new_stream().unwrap();
+    assert!(stream == context);
+    assert!(stream.is_same_stream(&stream.clone()));
+    assert_eq!(stream.is_same_stream(&context), context.is_native());
+
+    let a = Vector::from_slice_in(&[1 as TestType, 2, 3], &stream);
+    let mut b = Vector::from_slice_in(&[4 as TestType, 5, 6], &context);
+    assert_eq!((&a + &b).eval().to_vec(), vec![5, 7, 9]);
+    assert!((&a + &b).eval().context().is_same_stream(&stream));
+
+    b.set_stream(&stream).unwrap();
+    assert!(b.context().is_same_stream(&stream));
+    drop(a);
+    assert_eq!((&b * 2 as TestType).eval().to_vec(), vec![8, 10, 12]);
+    stream.finish().unwrap();
+
+    assert!(b.set_stream(&Context::native()).is_err());
+}
+
+#[test]
+fn write_after_read() {
+    use vector::Vector;
+    use context::Context;
+
+    let context = Context::default();
+    let stream = context.new_stream().unwrap();
+    let n = 1 << 16;
+    let x = Vector::zeros_in(n, &stream);
+    let mut a = Vector::filled_in(1 as TestType, n, &context);
+
+    // Reads a on the other stream, the write on the stream of a has to wait for it
+    let b = (&x + &a).eval();
+    assert_eq!(a.readers.lock().unwrap().len(), if context.is_native() { 0 } else { 1 });
+    a *= 0;
+    assert!(a.readers.lock().unwrap().is_empty());
+    assert_eq!(b.to_vec(), vec![1; n]);
+    assert_eq!(a.to_vec(), vec![0; n]);
+}
+
+#[test]
+fn concurrent_ops() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use std::thread;
+
+    let threads: Vec<_> = (0..8).map(|t| thread::spawn(move || {
+        for i in 0..50 {
+            let n = (t * 50 + i) as TestType % 20 + 1;
+            let a = Vector::from_vec(vec![n; 64]);
+            let b = Vector::filled(2 as TestType, 64);
+
+            let c = (&(&a + &b) * 3 as TestType).eval();
+            assert_eq!(c.to_vec(), vec![(n + 2) * 3; 64]);
+            assert_eq!((&c - &b).eval().sum(), ((n + 2) * 3 - 2) * 64);
+            assert_eq!((a.lazy() * &b + 1).eval().to_vec(), vec![n * 2 + 1; 64]);
+
+            let m = Matrix::filled(n, 4, 4);
+            let id = Matrix::<TestType>::identity(4);
+            assert_eq!(&m * &id, m);
+        }
+    })).collect();
+
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn buffer_interop() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use context::Context;
+
+    let v = Vector::from_vec(vec![1 as TestType, 2, 3]);
+    if v.context().is_native() {
+        assert!(v.into_buffer().is_err());
+        return;
+    }
+
+    // Shares the OpenCL context and queue of the default context
+    let context = Context::from_queue(v.context().queue().unwrap().clone()).unwrap();
+    let w = Vector::from_buffer_in((&v + &v).eval().into_buffer().unwrap(), &context);
+    assert_eq!(w.to_vec(), vec![2, 4, 6]);
+
+    let m = Matrix::from_buffer(w.into_buffer().unwrap(), 1, 3);
+    assert_eq!(m, Matrix::from_vec(vec![2, 4, 6], 1, 3));
+    let buffer = m.into_buffer().unwrap();
+    assert!(Matrix::try_from_buffer_in(buffer.clone(), 2, 2, &Context::default()).is_err());
+
+    // Wrapped buffers still belong to the caller and are not kept in the pool
+    drop(Vector::from_buffer_in(buffer, &context));
+    assert_eq!(context.pool_size(), 0);
+
+    let queue = context.queue().unwrap();
+    let out_of_order = ocl::Queue::new(&queue.context(), queue.device(),
+        Some(ocl::flags::QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE));
+    if let Ok(out_of_order) = out_of_order {
+        assert!(Context::from_queue(out_of_order).is_err());
+    }
+}
+
+#[test]
+fn expression_strings() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use error::Error;
+
+    let a = Vector::from_vec(vec![1 as TestType, 2, 3, 4]);
+    let b = Vector::from_vec(vec![4 as TestType, 3, 2, 1]);
+    let c = Vector::from_vec(vec![1 as TestType, 1, 2, 2]);
+
+    assert_eq!(a.map("x * x + 1").to_vec(), vec![2, 5, 10, 17]);
+    assert_eq!(a.map("(5 - x) * 2").to_vec(), vec![8, 6, 4, 2]);
+    assert_eq!(Vector::from_vec(vec![1.0f32, 3.0]).map("-x / 2 + 1.5e1").to_vec(), vec![14.5, 13.5]);
+    assert_eq!(a.map("x + i").to_vec(), vec![1, 3, 5, 7]);
+    assert_eq!(a.zip_with(&b, "a * b - 1").to_vec(), vec![3, 5, 5, 3]);
+    assert_eq!(Vector::zip_n(&[&a, &b, &c], "a * b - c").to_vec(), vec![3, 5, 4, 2]);
+
+    let m = Matrix::from_vec(vec![1 as TestType, 2, 3, 4], 2, 2);
+    assert_eq!(m.zip_with(&m.map("x * 2"), "b - a"), m);
+
+    assert!(a.try_map("x; x").is_err());
+    assert!(a.try_map("").is_err());
+    assert!(a.try_zip_with(&Vector::from_vec(vec![1 as TestType]), "a + b").is_err());
+    match Vector::<TestType>::try_zip_n(&[], "a") {
+        Err(Error::Expression(_)) => (),
+        _ => panic!("expected Error::Expression"),
+    }
+    // Integer elements only take integer literals, on every backend
+    assert_eq!(a.map("x * 3 / 2 + 16").to_vec(), vec![17, 19, 20, 22]);
+    for expr in &["x * 1.5", "x + 1e3", "x * 2.0f"] {
+        match a.try_map(expr) {
+            Err(Error::Expression(_)) => (),
+            _ => panic!("expected Error::Expression for {}", expr),
+        }
+    }
+    if a.context().is_native() {
+        match a.try_map("min(x, 2)") {
+            Err(Error::Expression(_)) => (),
+            _ => panic!("expected Error::Expression"),
+        }
+    }
+}
+
+#[test]
+fn map_kernels() {
+    use vector::Vector;
+    use context::Context;
+    use error::Error;
+
+    let default = Context::default();
+    if default.is_native() {
+        return;
+    }
+
+    let context = Context::from_queue(default.queue().unwrap().clone()).unwrap();
+    context.add_kernel_src("kernel void {T}_double_c(global {T}* C) {
+        C[i] *= 2;
+    }
+    kernel void {T}_inc_b(global {T}* C, global {T}* B) {
+        C[i] = B[i] + 1;
+    }").unwrap();
+    let none = None::<&::ocl::Buffer<TestType>>;
+    let mut double = context.create_kernel::<TestType>("double_c").arg_buf_named("C", none);
+    let mut inc = context.create_kernel::<TestType>("inc_b").arg_buf_named("C", none).arg_buf_named("B", none);
+
+    let mut v = Vector::from_vec_in(vec![1 as TestType, 2, 3], &context);
+    v.try_map_mut(&mut double).unwrap();
+    assert_eq!(v.to_vec(), vec![2, 4, 6]);
+    assert_eq!(v.try_map_lock_free(&mut inc).unwrap().to_vec(), vec![3, 5, 7]);
+
+    // Kernels run on the stream of the context they are created in
+    let stream = context.new_stream().unwrap();
+    let queue = stream.create_kernel::<TestType>("double_c").default_queue().unwrap().as_ptr();
+    assert_eq!(queue, stream.queue().unwrap().as_ptr());
+    assert!(queue != context.queue().unwrap().as_ptr());
+
+    let mut host = Vector::from_vec_in(vec![1 as TestType, 2, 3], &Context::native());
+    match host.try_map_mut(&mut double) {
+        Err(Error::NoDevice(_)) => (),
+        _ => panic!("expected Error::NoDevice"),
+    }
+    match host.try_map_lock_free(&mut inc) {
+        Err(Error::NoDevice(_)) => (),
+        _ => panic!("expected Error::NoDevice"),
+    }
+}
+
+#[test]
+fn typed_kernel_call() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use context::Context;
+    use error::Error;
+
+    let context = Context::default();
+    let x = Vector::from_vec_in(vec![1 as TestType, 2, 3], &context);
+    let mut y = Vector::from_vec_in(vec![4 as TestType, 5, 6], &context);
+
+    if context.is_native() {
+        match context.kernel_call::<TestType>("axpy").output(3).arg(&x).run() {
+            Err(Error::NoDevice(_)) => (),
+            _ => panic!("expected Error::NoDevice"),
+        }
+        return;
+    }
+
+    context.add_kernel_src("kernel void {T}_axpy(global {T}* C, global {T}* X, global {T}* Y, {T} a) {
+        C[i] = a * X[i] + Y[i];
+    }
+    kernel void {T}_scale(global {T}* C, {T} a) {
+        C[i] *= a;
+    }").unwrap();
+
+    let c = context.kernel_call::<TestType>("axpy").output(3).arg(&x).arg(&y).scalar(2 as TestType).run_vector().unwrap();
+    assert_eq!(c.to_vec(), vec![6, 9, 12]);
+
+    context.kernel_call::<TestType>("scale").arg_mut(&mut y).scalar(3 as TestType).run().unwrap();
+    assert_eq!(y.to_vec(), vec![12, 15, 18]);
+
+    let m = Matrix::from_vec_in(vec![1 as TestType, 2, 3, 4, 5, 6], 2, 3, &context);
+    let m2 = context.kernel_call::<TestType>("axpy").output_matrix(2, 3).matrix(&m).matrix(&m).scalar(1 as TestType)
+        .run_matrix().unwrap();
+    assert_eq!(m2, &m * 2 as TestType);
+
+    let short = Vector::from_vec_in(vec![1 as TestType], &context);
+    assert!(context.kernel_call::<TestType>("axpy").output(3).arg(&x).arg(&short).scalar(1 as TestType).run().is_err());
+    assert!(context.kernel_call::<TestType>("axpy").output(3).arg(&x).run().is_err());
+
+    let f = Vector::from_vec_in(vec![1.0f32, 2.0, 3.0], &context);
+    match context.kernel_call::<TestType>("axpy").output(3).arg(&f).arg(&y).scalar(1 as TestType).run() {
+        Err(Error::InvalidArgument(_)) => (),
+        _ => panic!("expected Error::InvalidArgument"),
+    }
+    match context.kernel_call::<TestType>("axpy").output(3).arg(&x).arg(&y).scalar(1.0f32).run() {
+        Err(Error::InvalidArgument(_)) => (),
+        _ => panic!("expected Error::InvalidArgument"),
+    }
+}
+
+#[test]
+fn random_fills() {
+    use vector::Vector;
+    use matrix::Matrix;
+    use random::{ self, Rng };
+    use context::Context;
+    use error::Error;
+
+    // Known answer of Philox4x32-10 for a zero counter and key
+    assert_eq!(random::philox(0, 0, 0), [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]);
+
+    let mut rng = Rng::new(42);
+    let mut a = Vector::<f32>::zeros(10_000);
+    a.fill_uniform(&mut rng, -1.0, 1.0);
+    let a = a.to_vec();
+    assert!(a.iter().all(|x| (-1.0..=1.0).contains(x)));
+    assert!(abs(a.iter().sum::<f32>() / a.len() as f32) < 0.05);
+
+    let mut b = Vector::<f32>::zeros(10_000);
+    b.fill_uniform(&mut rng, -1.0, 1.0);
+    assert_ne!(a, b.to_vec());
+    assert_eq!(rng.stream(), 2);
+
+    // Same seed and stream give the same data in every context
+    let mut c = Vector::<f32>::zeros_in(10_000, &Context::native());
+    c.fill_uniform(&mut Rng::new(42), -1.0, 1.0);
+    assert_eq!(a, c.to_vec());
+
+    let mut d = Vector::<i8>::zeros(1000);
+    d.fill_uniform(&mut rng, -3, 3);
+    let d = d.to_vec();
+    assert!(d.iter().all(|x| (-3..3).contains(x)));
+    assert!(d.contains(&-3) && d.contains(&2));
+
+    let mut m = Matrix::<f64>::zeros(100, 100);
+    m.fill_normal(&mut rng, 5.0, 2.0);
+    let mut m_host = vec![0.0; 100 * 100];
+    m.read_into(&mut m_host);
+    let m = m_host;
+    let mean = m.iter().sum::<f64>() / m.len() as f64;
+    let var = m.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / m.len() as f64;
+    assert!(are_close(mean, 5.0, 0.1));
+    assert!(are_close(var, 4.0, 0.2));
+
+    let mut e = Vector::<TestType>::zeros(10_000);
+    e.fill_bernoulli(&mut rng, 0.25);
+    let ones = e.to_vec().iter().filter(|&&x| x == 1).count();
+    assert!(ones > 2300 && ones < 2700);
+
+    match e.try_fill_bernoulli(&mut rng, 1.5) {
+        Err(Error::InvalidArgument(_)) => (),
+        _ => panic!("expected Error::InvalidArgument"),
+    }
+    match e.try_fill_uniform(&mut rng, 5, 5) {
+        Err(Error::InvalidArgument(_)) => (),
+        _ => panic!("expected Error::InvalidArgument"),
+    }
+}
+
+#[test]
+fn generators() {
+    use vector::Vector;
+    use matrix::Matrix;
+
+    assert_eq!(Vector::<TestType>::arange(3, 2, 4).to_vec(), vec![3, 5, 7, 9]);
+    assert_eq!(Vector::<f32>::arange(1.0, -0.5, 3).to_vec(), vec![1.0, 0.5, 0.0]);
+    assert_eq!(Vector::<TestType>::arange(1, 1, 0).len(), 0);
+
+    assert_eq!(Vector::<f32>::linspace(0.0, 1.0, 5).to_vec(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    assert_eq!(Vector::<f32>::linspace(2.0, 3.0, 1).to_vec(), vec![2.0]);
+    let l = Vector::<f32>::linspace(0.0, 0.3, 4).to_vec();
+    assert_eq!(l[3], 0.3);
+
+    let g = Vector::<f32>::logspace(0.0, 3.0, 4, 10.0).to_vec();
+    for (a, b) in g.iter().zip(&[1.0, 10.0, 100.0, 1000.0]) {
+        assert!(are_close(*a, *b, 0.01), "{} != {}", a, b);
+    }
+
+    let m = Matrix::<TestType>::from_index_expr(2, 3, "r * 10 + c");
+    assert_eq!(m, Matrix::from_vec(vec![0, 1, 2, 10, 11, 12], 2, 3));
+    assert_eq!(Matrix::<f32>::from_index_expr(2, 2, "(r + 1) / (c + 1)"),
+               Matrix::from_vec(vec![1.0, 0.5, 2.0, 1.0], 2, 2));
+    assert_eq!(Vector::<f32>::zeros(4).map("x + i / 2").to_vec(), vec![0.0, 0.5, 1.0, 1.5]);
+    assert!(Matrix::<TestType>::try_from_index_expr_in(2, 2, "r;", &::context::Context::default()).is_err());
+}
+
+#[test]
+fn vector_views() {
+    use vector::Vector;
+    use expr::Expr;
+    use context::Context;
+    use error::Error;
+    use std::mem::size_of;
+
+    // Views have to start at a multiple of a elements on the device
+    let context = Context::default();
+    let a = if context.is_native() { 1 } else { context.sub_buffer_align().unwrap() / size_of::<TestType>() };
+    let a = a.max(1);
+    let range = |start: usize, end: usize| (start as TestType..end as TestType).collect::<Vec<_>>();
+
+    let mut v = Vector::<TestType>::arange(0, 1, 10 * a);
+    let w = Vector::<TestType>::ones(10 * a);
+    let mut expected = range(0, 10 * a);
+
+    assert_eq!(v.view(2 * a..5 * a).to_vec(), range(2 * a, 5 * a));
+    assert_eq!(v.view(..), v);
+    assert_eq!(v.view(7 * a..).len(), 3 * a);
+    assert_eq!((&v.view(0..3 * a) + &v.view(3 * a..6 * a)).eval().to_vec(),
+               range(0, 3 * a).iter().map(|x| 2 * x + 3 * a as TestType).collect::<Vec<_>>());
+    assert_eq!((&v.view(0..3 * a) * &w.view(0..3 * a)).eval().to_vec(), range(0, 3 * a));
+    assert_eq!((&v.view(a..3 * a) * 2).eval().to_vec(), range(a, 3 * a).iter().map(|x| 2 * x).collect::<Vec<_>>());
+    assert_eq!(v.view(0..2 * a).sum(), range(0, 2 * a).iter().sum());
+
+    // Operators on views are lazy and fuse with the rest of the expression
+    {
+        let (lo, hi, ones) = (v.view(0..a), v.view(a..2 * a), w.view(0..a));
+        let e: Expr<TestType> = &(&lo + &ones) * &hi - 1;
+        assert_eq!(e.eval().to_vec(), range(0, a).iter().map(|x| (x + 1) * (x + a as TestType) - 1).collect::<Vec<_>>());
+    }
+
+    {
+        let (mut lo, mut hi) = v.split_at_mut(4 * a);
+        lo += &w.view(0..4 * a);
+        hi *= 2;
+        assert_eq!(lo.len(), 4 * a);
+        assert_eq!(hi.len(), 6 * a);
+        lo.try_finish().unwrap();
+    }
+    for (i, x) in expected.iter_mut().enumerate() {
+        *x = if i < 4 * a { *x + 1 } else { *x * 2 };
+    }
+    assert_eq!(v.to_vec(), expected);
+
+    let lens: Vec<usize> = v.chunks(4 * a).iter().map(|c| c.len()).collect();
+    assert_eq!(lens, vec![4 * a, 4 * a, 2 * a]);
+    for (idx, mut chunk) in v.chunks_mut(4 * a).into_iter().enumerate() {
+        chunk *= idx as TestType;
+    }
+    for (i, x) in expected.iter_mut().enumerate() {
+        *x *= (i / (4 * a)) as TestType;
+    }
+    assert_eq!(v.to_vec(), expected);
+
+    // Empty views work on every device, views of views and unaligned views only on the host
+    {
+        let (empty, all) = v.split_at(0);
+        assert_eq!((empty.len(), all.len()), (0, 10 * a));
+    }
+    assert_eq!(v.split_at(10 * a).1.to_vec(), Vec::<TestType>::new());
+    assert_eq!(v.view(3 * a..3 * a).len(), 0);
+    if context.is_native() {
+        assert_eq!(v.view(1..9).view(1..4).to_vec(), expected[2..5].to_vec());
+        {
+            let mut odd = v.view_mut(1..4);
+            odd.try_write_from(&[1, 2, 3]).unwrap();
+            odd.try_add_assign(&w.view(0..3)).unwrap();
+        }
+        expected[1..4].copy_from_slice(&[2, 3, 4]);
+        assert_eq!(v.to_vec(), expected);
+    } else {
+        match v.view(a..9 * a).try_view(a..4 * a) {
+            Err(Error::InvalidArgument(_)) => (),
+            r => panic!("view of a view: {:?}", r.map(|_| ())),
+        }
+        if a > 1 {
+            match v.try_view_mut(1..4) {
+                Err(Error::InvalidArgument(_)) => (),
+                r => panic!("unaligned view: {:?}", r.map(|_| ())),
+            }
+        }
+        assert_eq!(v.to_vec(), expected);
+    }
+
+    assert!(v.try_view(5..20 * a).is_err());
+    let (start, end) = (5, 3);
+    assert!(v.try_view(start..end).is_err());
+    assert!(v.try_split_at(10 * a + 1).is_err());
+    assert!(v.try_chunks(0).is_err());
+}
+
 use ::traits::Real;
 use ::std::ops::{ Sub, Neg };
 use ::std::cmp::PartialOrd;
 
-fn are_close<T: PartialOrd + Real>(a: T, b: T, delta: T) -> bool
-    where T: Sub<T, Output=T> + Neg<Output=T> + PartialOrd
+fn are_close<T>(a: T, b: T, delta: T) -> bool
+    where T: Real + Sub<T, Output=T> + Neg<Output=T> + PartialOrd
 {
     let d = abs(a - b);
     d < delta
 }
 
-fn abs<T: Real + PartialOrd>(x: T) -> T
-    where T: Neg<Output=T>
+fn abs<T>(x: T) -> T
+    where T: Real + PartialOrd + Neg<Output=T>
 {
     if x < T::zero() { -x } else { x }
 }
\ No newline at end of file
@@ -10,19 +10,26 @@ use ::std::ops::{
 };
 
 
-pub trait Parameter: ocl::OclPrm {
+pub trait Parameter: ocl::OclPrm + Send + Sync {
+    const ZERO: Self;
+    const ONE: Self;
+
     fn type_to_str() -> &'static str;
+
+    /// Convert like `value as T`, used for literals in expression strings on the native backend
+    fn from_f64(value: f64) -> Self;
 }
 
 
 pub trait Real: Parameter +
-    Add + AddAssign +
-    Sub + SubAssign +
-    Mul + MulAssign +
-    Div + DivAssign +
-    Neg
+    Add<Output=Self> + AddAssign +
+    Sub<Output=Self> + SubAssign +
+    Mul<Output=Self> + MulAssign +
+    Div<Output=Self> + DivAssign +
+    Neg<Output=Self>
 {
     fn sqrt(self) -> Self;
+    fn powf(self, exp: Self) -> Self;
     fn one() -> Self;
     fn zero() -> Self;
 }
@@ -30,6 +37,13 @@ pub trait Real: Parameter +
 macro_rules! impl_type_to_str {
     ($( $ty:ident ),+) => {
         $( impl Parameter for $ty {
+            const ZERO: Self = 0 as $ty;
+            const ONE: Self = 1 as $ty;
+
+            fn from_f64(value: f64) -> Self {
+                value as $ty
+            }
+
             fn type_to_str() -> &'static str {
                 match stringify!($ty) {
                     "u8" => "uchar",
@@ -63,6 +77,10 @@ macro_rules! impl_sqrt {
             fn sqrt(self) -> Self {
                 self.sqrt()
             }
+            #[allow(unconditional_recursion)]
+            fn powf(self, exp: Self) -> Self {
+                self.powf(exp)
+            }
             fn one() -> Self {
                 1.0 as $ty
             }
@@ -0,0 +1,141 @@
+//! Non-blocking transfers from the device to the host
+//!
+//! Vector::to_vec_async() and read_into_async() enqueue the read and return a Transfer right
+//! away. The host can keep working and poll the transfer with is_complete(), block on it with
+//! wait() or await it since Transfer implements Future.
+//!
+//! ```ignore
+//! let pending = v.to_vec_async();
+//! prepare_next_batch();
+//! let result = pending.wait()?;
+//! ```
+
+extern crate ocl;
+
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{ Arc, Mutex };
+use std::task::{ Context, Poll, Waker };
+
+use self::ocl::ffi::c_void;
+
+use error::Result;
+use traits::Parameter;
+
+/// Handle of a pending read into a host Vec
+///
+/// The Vec is owned by the transfer until the read has completed, dropping an unfinished
+/// transfer waits for the read.
+pub struct Transfer<T: Parameter> {
+    data: Option<Vec<T>>,
+    /// None if the data is already on the host, e.g. for the native backend
+    event: Option<ocl::Event>,
+    /// Waker of the last poll, set once the completion callback of event is registered
+    waker: Option<SharedWaker>,
+}
+
+/// Waker shared with the completion callback of a transfer, which takes it
+type SharedWaker = Arc<Mutex<Option<Waker>>>;
+
+impl<T: Parameter> Transfer<T> {
+    /// Transfer which is already complete
+    pub(crate) fn ready(data: Vec<T>) -> Transfer<T> {
+        Transfer { data: Some(data), event: None, waker: None }
+    }
+
+    /// Transfer completing with event, data must be the destination of the command of event
+    pub(crate) fn pending(data: Vec<T>, event: ocl::Event) -> Transfer<T> {
+        Transfer { data: Some(data), event: Some(event), waker: None }
+    }
+
+    /// Returns true if the data has arrived on the host, wait() will not block then
+    pub fn is_complete(&self) -> bool {
+        match self.event {
+            Some(ref event) => ocl::core::event_is_complete(event).unwrap_or(true),
+            None => true,
+        }
+    }
+
+    /// Block until the data has arrived on the host and return it
+    pub fn wait(mut self) -> Result<Vec<T>> {
+        // The event is kept until the wait succeeded so drop() does not free data in use
+        if let Some(ref event) = self.event {
+            event.wait_for()?;
+        }
+        self.event = None;
+        Ok(self.data.take().expect("Transfer already completed"))
+    }
+
+    /// Returns true if the event of the transfer has completed
+    fn poll_complete(&self) -> Result<bool> {
+        match self.event {
+            Some(ref event) => Ok(ocl::core::event_is_complete(event)?),
+            None => Ok(true),
+        }
+    }
+}
+
+// The data is never pinned, only moved out on completion
+impl<T: Parameter> Unpin for Transfer<T> {}
+
+impl<T: Parameter> Future for Transfer<T> {
+    type Output = Result<Vec<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<Vec<T>>> {
+        let this = self.get_mut();
+
+        match this.poll_complete() {
+            Ok(true) => {
+                this.event = None;
+                return Poll::Ready(Ok(this.data.take().expect("Transfer polled after completion")));
+            },
+            Ok(false) => (),
+            Err(e) => return Poll::Ready(Err(e)),
+        }
+
+        match this.waker {
+            Some(ref shared) => *shared.lock().unwrap() = Some(cx.waker().clone()),
+            None => {
+                // The callback is registered once and wakes the waker of the last poll
+                let shared: SharedWaker = Arc::new(Mutex::new(Some(cx.waker().clone())));
+                let user_data = Arc::into_raw(shared.clone()) as *mut c_void;
+                let registered = unsafe { this.event.as_ref().unwrap().set_callback(wake, user_data) };
+                if let Err(e) = registered {
+                    unsafe { drop(Arc::from_raw(user_data as *const Mutex<Option<Waker>>)) };
+                    return Poll::Ready(Err(e.into()));
+                }
+                this.waker = Some(shared);
+            },
+        }
+
+        // The callback may have taken the previous waker before the new one was stored
+        match this.poll_complete() {
+            Ok(true) => {
+                this.event = None;
+                Poll::Ready(Ok(this.data.take().expect("Transfer polled after completion")))
+            },
+            Ok(false) => Poll::Pending,
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+impl<T: Parameter> Drop for Transfer<T> {
+    fn drop(&mut self) {
+        // The device may still write into data, which is leaked if that can not be ruled out
+        if let Some(ref event) = self.event {
+            if event.wait_for().is_err() {
+                mem::forget(self.data.take());
+            }
+        }
+    }
+}
+
+extern "C" fn wake(_event: ocl::ffi::cl_event, _status: i32, user_data: *mut c_void) {
+    let shared = unsafe { Arc::from_raw(user_data as *const Mutex<Option<Waker>>) };
+    let waker = shared.lock().unwrap().take();
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
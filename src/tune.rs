@@ -0,0 +1,223 @@
+//! Work-group size tuning
+//!
+//! The launch parameters of the reduction and matrix kernels are benchmarked the first time a
+//! kernel is launched for a size bucket on a device, buckets being powers of two. The fastest
+//! parameters are kept in a tuning table which is stored in a file, so later runs load them
+//! instead of benchmarking again.
+
+extern crate ocl;
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::path::PathBuf;
+use std::sync::{ LazyLock, Mutex };
+use std::time::{ Duration, Instant };
+use std::fs;
+
+use error::{ Error, Result };
+use cache;
+use KernelParams;
+
+/// Path of the tuning file, None if tuning results are not persisted
+pub(crate) static TUNING_FILE: LazyLock<Mutex<Option<PathBuf>>> = LazyLock::new(|| Mutex::new(Some(
+    match ::std::env::var_os("LINEAR_ALGEBRA_TUNING_FILE") {
+        Some(file) => PathBuf::from(file),
+        None => ::std::env::temp_dir().join("linear_algebra_kernels").join("tuning.txt"),
+    }
+)));
+
+/// Tuned parameters by device, kernel and size bucket, loaded from the file on first use
+static TABLE: Mutex<Option<HashMap<String, KernelParams>>> = Mutex::new(None);
+
+/// Forget the loaded table so it is read again from the current tuning file
+pub(crate) fn reset() {
+    *TABLE.lock().unwrap() = None;
+}
+
+/// Launch the kernel named kernel_name for len elements with the tuned parameters
+///
+/// If the kernel has not been tuned for the size bucket of len on the device of queue yet, run is
+/// called once for every candidate and the result of the fastest is returned. run has to enqueue
+/// the kernel on queue, which is finished before measuring the time.
+///
+/// candidates have to be computed for len, the table entry of the bucket only selects one of them
+/// since the global work size depends on len.
+pub(crate) fn launch<R, F>(queue: &ocl::Queue, kernel_name: &str, len: usize, candidates: &[KernelParams], mut run: F) -> Result<R>
+    where F: FnMut(KernelParams) -> Result<R>
+{
+    let key = table_key(queue.device(), kernel_name, len);
+
+    if let Some(params) = get(&key) {
+        return run(tuned_candidate(candidates, params));
+    }
+
+    let mut best: Option<(Duration, KernelParams, R)> = None;
+    for &params in candidates {
+        // Wait for previous work so it is not included in the measurement
+        queue.finish()?;
+
+        let start = Instant::now();
+        let res = run(params)?;
+        queue.finish()?;
+        let time = start.elapsed();
+
+        let is_best = match best {
+            Some((best_time, _, _)) => time < best_time,
+            None => true,
+        };
+        if is_best {
+            best = Some((time, params, res));
+        }
+    }
+
+    match best {
+        Some((_, params, res)) => {
+            insert(key, params);
+            Ok(res)
+        },
+        None => Err(Error::OpenCl("No launch parameters to tune".into())),
+    }
+}
+
+/// Candidates for the reduction kernels, work group sizes are powers of two since the
+/// reduction halves the work group in every step
+pub(crate) fn reduction_candidates(kernel: &ocl::Kernel, len: usize) -> Result<Vec<KernelParams>> {
+    let max_size = max_work_group_size(kernel)?;
+
+    let mut candidates = Vec::new();
+    let mut work_group_size = 16;
+    while work_group_size <= max_size && work_group_size <= 1024 {
+        for &work_group_count in &[16, 32, 64, 128, 256] {
+            if work_group_count <= len {
+                candidates.push(KernelParams {
+                    work_group_size,
+                    work_group_count,
+                    global_work_size: work_group_size * work_group_count,
+                });
+            }
+        }
+        work_group_size *= 2;
+    }
+
+    Ok(candidates)
+}
+
+/// Candidates for kernels run with one work item per element, global_work_size has to be len
+/// since the kernels do no bounds checking. A work_group_size of 0 lets OpenCL choose.
+pub(crate) fn elementwise_candidates(kernel: &ocl::Kernel, len: usize) -> Result<Vec<KernelParams>> {
+    let max_size = max_work_group_size(kernel)?;
+
+    let mut candidates = vec![KernelParams { work_group_size: 0, work_group_count: 0, global_work_size: len }];
+    for &work_group_size in &[16, 32, 64, 128, 256] {
+        if work_group_size <= max_size && len % work_group_size == 0 {
+            candidates.push(KernelParams {
+                work_group_size,
+                work_group_count: len / work_group_size,
+                global_work_size: len,
+            });
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Candidate with the work group size and count of the tuned parameters, which may have been
+/// tuned for another length of the same bucket
+///
+/// Elementwise kernels have one work group per work_group_size elements, so only the size has to
+/// match. Falls back to the first candidate if the tuned work group size does not fit.
+pub(crate) fn tuned_candidate(candidates: &[KernelParams], tuned: KernelParams) -> KernelParams {
+    *candidates.iter()
+        .find(|c| c.work_group_size == tuned.work_group_size && c.work_group_count == tuned.work_group_count)
+        .or_else(|| candidates.iter().find(|c| c.work_group_size == tuned.work_group_size))
+        .unwrap_or(&candidates[0])
+}
+
+fn max_work_group_size(kernel: &ocl::Kernel) -> Result<usize> {
+    use ocl::enums::{ KernelWorkGroupInfoResult, KernelWorkGroupInfo };
+
+    let device = ocl::Device::from(kernel.devices()?[0]);
+    match kernel.wg_info(device, KernelWorkGroupInfo::WorkGroupSize) {
+        KernelWorkGroupInfoResult::WorkGroupSize(s) => Ok(s),
+        KernelWorkGroupInfoResult::Error(e) => Err(Error::OpenCl(*e)),
+        _ => Err(Error::OpenCl("Unexpected work group info result".into())),
+    }
+}
+
+/// Key of the tuning table, the size bucket is the number of bits needed for len
+fn table_key(device: ocl::Device, kernel_name: &str, len: usize) -> String {
+    let mut hasher = DefaultHasher::new();
+    cache::hash_device(device, &mut hasher);
+
+    let bucket = len.next_power_of_two().trailing_zeros();
+    format!("{:016x}:{}:{}", hasher.finish(), kernel_name, bucket)
+}
+
+fn get(key: &str) -> Option<KernelParams> {
+    let mut table = TABLE.lock().unwrap();
+    if table.is_none() {
+        *table = Some(load());
+    }
+
+    table.as_ref().unwrap().get(key).cloned()
+}
+
+fn insert(key: String, params: KernelParams) {
+    let mut table = TABLE.lock().unwrap();
+    if table.is_none() {
+        *table = Some(load());
+    }
+
+    let table = table.as_mut().unwrap();
+    table.insert(key, params);
+    save(table);
+}
+
+fn load() -> HashMap<String, KernelParams> {
+    let path = TUNING_FILE.lock().unwrap().clone();
+    match path.and_then(|path| fs::read_to_string(path).ok()) {
+        Some(content) => parse_table(&content),
+        None => HashMap::new(),
+    }
+}
+
+/// Tuning results are only an optimization, failing to write them is not an error
+fn save(table: &HashMap<String, KernelParams>) {
+    if let Some(ref path) = *TUNING_FILE.lock().unwrap() {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(path, format_table(table));
+    }
+}
+
+/// Parse the tuning file, one line per entry with the key followed by work_group_size,
+/// work_group_count and global_work_size. Malformed lines are skipped.
+pub(crate) fn parse_table(content: &str) -> HashMap<String, KernelParams> {
+    let mut table = HashMap::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            continue;
+        }
+
+        let sizes: Vec<usize> = fields[1..].iter().filter_map(|s| s.parse().ok()).collect();
+        if sizes.len() == 3 {
+            table.insert(fields[0].to_owned(), KernelParams {
+                work_group_size: sizes[0],
+                work_group_count: sizes[1],
+                global_work_size: sizes[2],
+            });
+        }
+    }
+    table
+}
+
+pub(crate) fn format_table(table: &HashMap<String, KernelParams>) -> String {
+    let mut lines: Vec<String> = table.iter()
+        .map(|(key, p)| format!("{} {} {} {}\n", key, p.work_group_size, p.work_group_count, p.global_work_size))
+        .collect();
+    lines.sort();
+    lines.concat()
+}
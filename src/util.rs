@@ -3,12 +3,12 @@
 
 /// Read u64 to from file.
 /// NOTE! File will be interpreted in the current systems endianness
-pub unsafe fn read_u64(file: &mut ::std::fs::File) -> Result<u64, ::std::io::Error> {
+pub fn read_u64(file: &mut ::std::fs::File) -> Result<u64, ::std::io::Error> {
     use std::io::Read;
 
     let mut elem_size = [0u8; 8];
     file.read_exact(&mut elem_size)?;
-    Ok(::std::mem::transmute(elem_size))
+    Ok(u64::from_ne_bytes(elem_size))
 }
 
 /// Write u64 to from file.
@@ -16,6 +16,5 @@ pub unsafe fn read_u64(file: &mut ::std::fs::File) -> Result<u64, ::std::io::Err
 pub fn write_u64(file: &mut ::std::fs::File, x: u64) -> Result<(), ::std::io::Error> {
     use std::io::Write;
 
-    let elem_size: [u8; 8] = unsafe { ::std::mem::transmute(x) };
-    file.write_all(&elem_size)
+    file.write_all(&x.to_ne_bytes())
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 
 extern crate ocl;
 
-use ocl::{Buffer, MemFlags, Kernel};
+use ocl::{Buffer, MemFlags, Kernel, EventList};
 
 use matrix::Matrix;
 
@@ -9,156 +9,1311 @@ use ::std::ops::{
     Add, AddAssign,
     Sub, SubAssign,
     Mul, MulAssign,
-    Div, DivAssign
+    Div, DivAssign,
+    Bound, RangeBounds
 };
+use ::std::sync::Mutex;
 
 use traits::*;
 use util::*;
 
-use KernelsGuard;
+use Kernels;
+
+use context::{ Context, requires_opencl };
+use error::{ Error, Result };
+use expr::{ self, Expr };
+use device::DeviceProperties;
+use transfer::Transfer;
+use random::{ self, Random, RandomNormal, Rng };
+use view::{ VectorView, VectorViewMut };
+use native;
+use tune;
+use pool;
+
+/// Vector stored and computed on the device of its context
+///
+/// Operations on vectors stored on an OpenCL device return as soon as their kernel is enqueued.
+/// Each vector keeps the events of the commands writing to it and of the commands reading it
+/// since, later operations wait on those events on the device. The host only blocks when reading
+/// the data, e.g. in to_vec(), sum(), dot() or finish().
+pub struct Vector<T>
+    where T: Parameter
+{
+    pub(crate) data: Data<T>,
+    pub(crate) context: Context,
+
+    /// Events that have to complete before the data is valid, always empty for host data
+    pub(crate) events: EventList,
+
+    /// Events of commands reading the data since the last write, which have to complete before
+    /// it is overwritten, e.g. by a command on another stream
+    pub(crate) readers: Mutex<EventList>,
+
+    /// Number of elements, buffers taken from the pool may be longer
+    pub(crate) len: usize,
+}
+
+/// Storage of a Vector's elements
+pub(crate) enum Data<T: Parameter> {
+    /// Buffer on the OpenCL device
+    Ocl(Buffer<T>),
+
+    /// Host memory, used by the native backend
+    Host(Vec<T>),
+}
+
+impl<T: Parameter> Vector<T> {
+    pub fn new(default_val: T, size: usize) -> Vector<T>
+        where T: Clone
+    {
+        Vector::new_in(default_val, size, &Context::default())
+    }
+
+    /// Same as new() but creates the vector in the specified context
+    pub fn new_in(default_val: T, size: usize, context: &Context) -> Vector<T>
+        where T: Clone
+    {
+        Vector::filled_in(default_val, size, context)
+    }
+
+    /// Create vector with every element set to value, the buffer is filled on the device
+    /// without copying from the host
+    pub fn filled(value: T, size: usize) -> Vector<T> {
+        Vector::filled_in(value, size, &Context::default())
+    }
+
+    /// Same as filled() but creates the vector in the specified context
+    pub fn filled_in(value: T, size: usize, context: &Context) -> Vector<T> {
+        Vector::try_filled_in(value, size, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as filled_in() but returns an error if the buffer could not be allocated
+    pub fn try_filled_in(value: T, size: usize, context: &Context) -> Result<Vector<T>> {
+        if context.is_native() {
+            return Ok(Vector::from_host(vec![value; size], context));
+        }
+
+        let mut res = unsafe { Vector::try_uninitialized_in(size, context)? };
+        if size > 0 {
+            let mut event = ocl::Event::empty();
+            res.buf().cmd().queue(context.cl_queue()?).fill(value, Some(size)).ewait(&res.write_wait_list()).enew(&mut event).enq()?;
+            context.profile_transfer::<T>("fill", &event);
+            res.set_event(event);
+        }
+        Ok(res)
+    }
+
+    /// Create vector with every element set to 0
+    pub fn zeros(size: usize) -> Vector<T> {
+        Vector::filled(T::ZERO, size)
+    }
+
+    /// Same as zeros() but creates the vector in the specified context
+    pub fn zeros_in(size: usize, context: &Context) -> Vector<T> {
+        Vector::filled_in(T::ZERO, size, context)
+    }
+
+    /// Create vector with every element set to 1
+    pub fn ones(size: usize) -> Vector<T> {
+        Vector::filled(T::ONE, size)
+    }
+
+    /// Same as ones() but creates the vector in the specified context
+    pub fn ones_in(size: usize, context: &Context) -> Vector<T> {
+        Vector::filled_in(T::ONE, size, context)
+    }
+
+    /// Wrap an existing buffer of the default context without copying it, see from_buffer_in()
+    pub fn from_buffer(buffer: Buffer<T>) -> Vector<T> {
+        Vector::from_buffer_in(buffer, &Context::default())
+    }
+
+    /// Wrap an existing buffer without copying it, the buffer has to belong to the OpenCL
+    /// context of context, e.g. one created with Context::from_queue()
+    ///
+    /// Commands writing to the buffer have to be complete or enqueued on the queue of context.
+    /// The buffer is not returned to the pool of context when the vector is dropped.
+    pub fn from_buffer_in(buffer: Buffer<T>, context: &Context) -> Vector<T> {
+        Vector::try_from_buffer_in(buffer, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_buffer_in() but returns an error instead of panicking, fails with
+    /// Error::ContextMismatch if buffer belongs to another OpenCL context
+    pub fn try_from_buffer_in(buffer: Buffer<T>, context: &Context) -> Result<Vector<T>> {
+        use ocl::enums::{ MemInfo, MemInfoResult };
+
+        let cl_context = context.cl_context().ok_or_else(requires_opencl)?;
+        match buffer.mem_info(MemInfo::Context) {
+            MemInfoResult::Context(ref c) if c.as_ptr() == cl_context.as_ptr() => (),
+            _ => return Err(Error::ContextMismatch),
+        }
+
+        Ok(Vector {
+            len: buffer.len(),
+            data: Data::Ocl(buffer),
+            context: context.clone(),
+            events: EventList::new(),
+            readers: Mutex::new(EventList::new()),
+        })
+    }
+
+    /// Unwrap the buffer of the vector for use by other OpenCL code, waits until all commands
+    /// writing to the vector have completed
+    ///
+    /// Fails if the vector is stored on the host by the native backend.
+    pub fn into_buffer(mut self) -> Result<Buffer<T>> {
+        self.try_finish()?;
+        match ::std::mem::replace(&mut self.data, Data::Host(Vec::new())) {
+            Data::Ocl(buff) => {
+                self.context.disown_buffer(&buff);
+                if buff.len() == self.len {
+                    Ok(buff)
+                } else {
+                    // Buffers are allocated for the size class of the length
+                    Ok(buff.create_sub_buffer(None, 0, self.len)?)
+                }
+            },
+            Data::Host(v) => {
+                self.data = Data::Host(v);
+                Err(Error::NoDevice("Vector is stored on the host by the native backend".to_owned()))
+            },
+        }
+    }
+
+    /// Create vector in the specified context without initializing its elements, does not take
+    /// any lock
+    ///
+    /// # Safety
+    ///
+    /// The elements have arbitrary values, they have to be written before they are read.
+    pub unsafe fn uninitialized_lock_free(size: usize, context: &Context) -> Vector<T> {
+        Vector::try_uninitialized_in(size, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// # Safety
+    ///
+    /// The elements have arbitrary values, they have to be written before they are read.
+    pub unsafe fn uninitialized(size: usize) -> Vector<T> {
+        Vector::uninitialized_in(size, &Context::default())
+    }
+
+    /// Same as uninitialized() but creates the vector in the specified context
+    ///
+    /// # Safety
+    ///
+    /// See uninitialized().
+    pub unsafe fn uninitialized_in(size: usize, context: &Context) -> Vector<T> {
+        Vector::uninitialized_lock_free(size, context)
+    }
+
+    /// Same as uninitialized_in() but returns an error if the buffer could not be allocated
+    ///
+    /// # Safety
+    ///
+    /// See uninitialized().
+    pub unsafe fn try_uninitialized_in(size: usize, context: &Context) -> Result<Vector<T>> {
+        if context.is_native() {
+            return Ok(Vector::from_host(vec![T::default(); size], context));
+        }
+
+        let (buff, events) = match context.take_buffer(size) {
+            Some(pooled) => pooled,
+            None => {
+                let buff = Buffer::builder()
+                    .queue(context.cl_queue()?.clone())
+                    .flags(MemFlags::new().read_write())
+                    .dims(pool::size_class(size))
+                    .build()?;
+                context.own_buffer(&buff);
+                (buff, EventList::new())
+            },
+        };
+
+        Ok(Vector {
+            data: Data::Ocl(buff),
+            context: context.clone(),
+            events,
+            readers: Mutex::new(EventList::new()),
+            len: size,
+        })
+    }
+
+    pub fn from_vec(v: Vec<T>) -> Vector<T> {
+        Vector::from_vec_in(v, &Context::default())
+    }
+
+    /// Same as from_vec() but creates the vector in the specified context
+    pub fn from_vec_in(v: Vec<T>, context: &Context) -> Vector<T> {
+        Vector::try_from_vec_in(v, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_vec() but returns an error instead of panicking
+    pub fn try_from_vec(v: Vec<T>) -> Result<Vector<T>> {
+        Vector::try_from_vec_in(v, &Context::try_default()?)
+    }
+
+    /// Same as from_vec_in() but returns an error if the buffer could not be allocated
+    pub fn try_from_vec_in(v: Vec<T>, context: &Context) -> Result<Vector<T>> {
+        if context.is_native() {
+            return Ok(Vector::from_host(v, context));
+        }
+
+        Vector::try_from_slice_in(&v, context)
+    }
+
+    /// Same as from_vec() but copies the elements from a slice
+    pub fn from_slice(src: &[T]) -> Vector<T> {
+        Vector::from_slice_in(src, &Context::default())
+    }
+
+    /// Same as from_slice() but creates the vector in the specified context
+    pub fn from_slice_in(src: &[T], context: &Context) -> Vector<T> {
+        Vector::try_from_slice_in(src, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_slice_in() but returns an error if the buffer could not be allocated
+    pub fn try_from_slice_in(src: &[T], context: &Context) -> Result<Vector<T>> {
+        if context.is_native() {
+            return Ok(Vector::from_host(src.to_vec(), context));
+        }
+
+        let mut res = unsafe { Vector::try_uninitialized_in(src.len(), context)? };
+        res.try_write_from(src)?;
+        Ok(res)
+    }
+
+    pub(crate) fn from_host(v: Vec<T>, context: &Context) -> Vector<T> {
+        Vector {
+            len: v.len(),
+            data: Data::Host(v),
+            context: context.clone(),
+            events: EventList::new(),
+            readers: Mutex::new(EventList::new()),
+        }
+    }
+
+    /// Get the context this vector lives in
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Move the vector to another stream of its context, operations with the vector as first
+    /// operand are enqueued there from now on, see Context::new_stream()
+    ///
+    /// Fails with Error::ContextMismatch if stream belongs to another context.
+    pub fn set_stream(&mut self, stream: &Context) -> Result<()> {
+        self.context.check_same(stream)?;
+        self.context = stream.clone();
+        Ok(())
+    }
+
+    /// Properties of the OpenCL device this vector is stored on, None if it is stored on the
+    /// host by the native backend
+    pub fn device(&self) -> Option<DeviceProperties> {
+        self.context.device()
+    }
+
+    /// Start a lazy expression, see expr::Expr, the operators on &Vector start one as well
+    pub fn lazy<'a>(&'a self) -> Expr<'a, T> {
+        Expr::from_vector(self)
+    }
+
+    /// Events of the commands writing to this vector which might not have completed yet
+    ///
+    /// Custom kernels using get_buffer() should wait on these events.
+    pub fn events(&self) -> &EventList {
+        &self.events
+    }
+
+    /// Block until all commands writing to this vector have completed
+    pub fn finish(&self) {
+        self.try_finish().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as finish() but returns an error instead of panicking
+    pub fn try_finish(&self) -> Result<()> {
+        Ok(self.events.wait_for()?)
+    }
+
+    /// Events a command reading inputs and writing to output has to wait for, see
+    /// write_wait_list()
+    pub(crate) fn wait_list<U: Parameter>(inputs: &[&Vector<T>], output: &Vector<U>) -> EventList {
+        let mut events = output.write_wait_list();
+        for v in inputs {
+            for e in v.events.iter() {
+                events.push(e.clone());
+            }
+        }
+        events
+    }
+
+    /// Events a command writing to this vector has to wait for, the previous writes and the
+    /// reads since
+    pub(crate) fn write_wait_list(&self) -> EventList {
+        let mut events = self.events.clone();
+        for e in self.readers.lock().unwrap().iter() {
+            events.push(e.clone());
+        }
+        events
+    }
+
+    /// Set the event of the command writing to this vector, the command has to wait for
+    /// write_wait_list()
+    pub(crate) fn set_event(&mut self, event: ocl::Event) {
+        self.events = EventList::from(event);
+        self.readers.lock().unwrap().clear();
+    }
+
+    /// Record the event of a command reading the vectors, later writes wait for it
+    pub(crate) fn add_read_event(vectors: &[&Vector<T>], event: &ocl::Event) {
+        for v in vectors {
+            let mut readers = v.readers.lock().unwrap();
+            // Drop completed reads so the list does not grow while the vector is only read
+            let pending: Vec<ocl::Event> = readers.iter()
+                .filter(|e| !e.is_complete().unwrap_or(false))
+                .cloned()
+                .collect();
+            let mut list = EventList::new();
+            for e in pending {
+                list.push(e);
+            }
+            list.push(event.clone());
+            *readers = list;
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.try_to_vec().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as to_vec() but returns an error if the data could not be read from the device
+    pub fn try_to_vec(&self) -> Result<Vec<T>> {
+        if let Data::Host(ref v) = self.data {
+            return Ok(v.clone());
+        }
+
+        let mut res = vec![T::ZERO; self.len()];
+        self.try_read_into(&mut res)?;
+        Ok(res)
+    }
+
+    /// Same as to_vec() but returns without waiting for the data, see Transfer
+    pub fn to_vec_async(&self) -> Transfer<T> {
+        self.try_to_vec_async().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as to_vec_async() but returns an error if the read could not be enqueued
+    pub fn try_to_vec_async(&self) -> Result<Transfer<T>> {
+        if let Data::Host(ref v) = self.data {
+            return Ok(Transfer::ready(v.clone()));
+        }
+
+        self.try_read_into_async(vec![T::ZERO; self.len()])
+    }
+
+    /// Read all elements into dst without waiting for the data, dst has to have the same length
+    /// as the vector and is handed back by the returned Transfer
+    ///
+    /// Allows reusing host buffers across transfers.
+    pub fn read_into_async(&self, dst: Vec<T>) -> Transfer<T> {
+        self.try_read_into_async(dst).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as read_into_async() but returns an error instead of panicking
+    pub fn try_read_into_async(&self, mut dst: Vec<T>) -> Result<Transfer<T>> {
+        check_len(self.len(), dst.len())?;
+
+        let buff = match self.data {
+            Data::Host(ref v) => {
+                dst.copy_from_slice(v);
+                return Ok(Transfer::ready(dst));
+            },
+            Data::Ocl(ref buff) => buff,
+        };
+        if dst.is_empty() {
+            return Ok(Transfer::ready(dst));
+        }
+
+        let mut event = ocl::Event::empty();
+        // The heap memory of dst does not move while the Transfer owns it, and the Transfer
+        // waits for event before releasing it
+        unsafe {
+            buff.read(&mut dst).queue(self.context.cl_queue()?).block(false).ewait(&self.events).enew(&mut event).enq()?;
+        }
+        self.context.profile_transfer::<T>("read", &event);
+        Vector::add_read_event(&[self], &event);
+        Ok(Transfer::pending(dst, event))
+    }
+
+    /// Copy all elements into dst, which has to have the same length as the vector
+    pub fn read_into(&self, dst: &mut [T]) {
+        self.try_read_into(dst).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as read_into() but returns an error instead of panicking
+    pub fn try_read_into(&self, dst: &mut [T]) -> Result<()> {
+        check_len(self.len(), dst.len())?;
+        self.try_read_range_into(0, dst)
+    }
+
+    /// Copy dst.len() elements starting at offset into dst
+    pub fn read_range_into(&self, offset: usize, dst: &mut [T]) {
+        self.try_read_range_into(offset, dst).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as read_range_into() but returns an error instead of panicking, fails with
+    /// Error::ShapeMismatch if the range is out of bounds
+    pub fn try_read_range_into(&self, offset: usize, dst: &mut [T]) -> Result<()> {
+        check_range(offset, dst.len(), self.len())?;
+
+        match self.data {
+            Data::Host(ref v) => dst.copy_from_slice(&v[offset..offset + dst.len()]),
+            Data::Ocl(ref buff) => if !dst.is_empty() {
+                let mut event = ocl::Event::empty();
+                buff.read(dst).queue(self.context.cl_queue()?).offset(offset).ewait(&self.events).enew(&mut event).enq()?;
+                self.context.profile_transfer::<T>("read", &event);
+                Vector::add_read_event(&[self], &event);
+            },
+        }
+        Ok(())
+    }
+
+    /// Overwrite all elements with src, which has to have the same length as the vector
+    pub fn write_from(&mut self, src: &[T]) {
+        self.try_write_from(src).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as write_from() but returns an error instead of panicking
+    pub fn try_write_from(&mut self, src: &[T]) -> Result<()> {
+        check_len(self.len(), src.len())?;
+        self.try_write_range_from(0, src)
+    }
+
+    /// Overwrite src.len() elements starting at offset with src
+    pub fn write_range_from(&mut self, offset: usize, src: &[T]) {
+        self.try_write_range_from(offset, src).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as write_range_from() but returns an error instead of panicking, fails with
+    /// Error::ShapeMismatch if the range is out of bounds
+    pub fn try_write_range_from(&mut self, offset: usize, src: &[T]) -> Result<()> {
+        check_range(offset, src.len(), self.len())?;
+
+        let event = match self.data {
+            Data::Host(ref mut v) => {
+                v[offset..offset + src.len()].copy_from_slice(src);
+                return Ok(());
+            },
+            Data::Ocl(ref buff) => {
+                if src.is_empty() {
+                    return Ok(());
+                }
+                let mut event = ocl::Event::empty();
+                buff.write(src).queue(self.context.cl_queue()?).offset(offset).ewait(&self.write_wait_list()).enew(&mut event).enq()?;
+                event
+            },
+        };
+
+        self.context.profile_transfer::<T>("write", &event);
+        self.set_event(event);
+        Ok(())
+    }
+
+    /// Borrow the elements in range without copying them, see view::VectorView
+    pub fn view<R: RangeBounds<usize>>(&self, range: R) -> VectorView<'_, T> {
+        self.try_view(range).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as view() but returns an error instead of panicking, fails with
+    /// Error::ShapeMismatch if range is out of bounds and with Error::InvalidArgument if the
+    /// vector is a view itself or range does not start aligned on an OpenCL device
+    pub fn try_view<R: RangeBounds<usize>>(&self, range: R) -> Result<VectorView<'_, T>> {
+        let (offset, len) = resolve_range(range, self.len())?;
+        VectorView::new(self, offset, len)
+    }
+
+    /// Borrow the elements in range mutably without copying them, see view::VectorViewMut
+    pub fn view_mut<R: RangeBounds<usize>>(&mut self, range: R) -> VectorViewMut<'_, T> {
+        self.try_view_mut(range).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as view_mut() but returns an error instead of panicking, see try_view()
+    pub fn try_view_mut<R: RangeBounds<usize>>(&mut self, range: R) -> Result<VectorViewMut<'_, T>> {
+        let (offset, len) = resolve_range(range, self.len())?;
+        unsafe { VectorViewMut::new(self, offset, len) }
+    }
+
+    /// Borrow the elements before mid and from mid on as two views
+    pub fn split_at(&self, mid: usize) -> (VectorView<'_, T>, VectorView<'_, T>) {
+        self.try_split_at(mid).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as split_at() but returns an error instead of panicking, see try_view()
+    pub fn try_split_at(&self, mid: usize) -> Result<(VectorView<'_, T>, VectorView<'_, T>)> {
+        check_range(0, mid, self.len())?;
+        Ok((VectorView::new(self, 0, mid)?, VectorView::new(self, mid, self.len() - mid)?))
+    }
+
+    /// Same as split_at() but borrows the two halves mutably
+    pub fn split_at_mut(&mut self, mid: usize) -> (VectorViewMut<'_, T>, VectorViewMut<'_, T>) {
+        self.try_split_at_mut(mid).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as split_at_mut() but returns an error instead of panicking, see try_view()
+    pub fn try_split_at_mut(&mut self, mid: usize) -> Result<(VectorViewMut<'_, T>, VectorViewMut<'_, T>)> {
+        check_range(0, mid, self.len())?;
+        let len = self.len();
+        let parent: *mut Vector<T> = self;
+        // The ranges are disjoint
+        unsafe { Ok((VectorViewMut::new(parent, 0, mid)?, VectorViewMut::new(parent, mid, len - mid)?)) }
+    }
+
+    /// Borrow the elements in views of chunk_size elements, the last view may be shorter
+    pub fn chunks(&self, chunk_size: usize) -> Vec<VectorView<'_, T>> {
+        self.try_chunks(chunk_size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as chunks() but returns an error instead of panicking, chunk_size has to be
+    /// greater than 0, see try_view()
+    pub fn try_chunks(&self, chunk_size: usize) -> Result<Vec<VectorView<'_, T>>> {
+        chunk_ranges(self.len(), chunk_size)?.into_iter()
+            .map(|(offset, len)| VectorView::new(self, offset, len))
+            .collect()
+    }
+
+    /// Same as chunks() but borrows the chunks mutably
+    pub fn chunks_mut(&mut self, chunk_size: usize) -> Vec<VectorViewMut<'_, T>> {
+        self.try_chunks_mut(chunk_size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as chunks_mut() but returns an error instead of panicking, see try_chunks()
+    pub fn try_chunks_mut(&mut self, chunk_size: usize) -> Result<Vec<VectorViewMut<'_, T>>> {
+        let parent: *mut Vector<T> = self;
+        // The chunks are disjoint
+        chunk_ranges(self.len(), chunk_size)?.into_iter()
+            .map(|(offset, len)| unsafe { VectorViewMut::new(parent, offset, len) })
+            .collect()
+    }
+
+    /// OpenCL buffer of vector, panics if the vector is stored on the host by the native backend
+    pub(crate) fn buf(&self) -> &Buffer<T> {
+        match self.data {
+            Data::Ocl(ref buff) => buff,
+            Data::Host(_) => panic!("Operation requires OpenCL but vector is stored on the host"),
+        }
+    }
+
+    /// OpenCL buffer of vector, panics if the vector is stored on the host by the native backend
+    pub(crate) fn buf_mut(&mut self) -> &mut Buffer<T> {
+        match self.data {
+            Data::Ocl(ref mut buff) => buff,
+            Data::Host(_) => panic!("Operation requires OpenCL but vector is stored on the host"),
+        }
+    }
+
+    pub fn generate(kernel: &mut Kernel, size: usize) -> Vector<T> {
+        Vector::generate_in(kernel, size, &Context::default())
+    }
+
+    /// Same as generate() but creates the vector in the specified context, kernel has to belong
+    /// to that context as well
+    pub fn generate_in(kernel: &mut Kernel, size: usize, context: &Context) -> Vector<T> {
+        Vector::try_generate_in(kernel, size, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as generate_in() but returns an error instead of panicking
+    pub fn try_generate_in(kernel: &mut Kernel, size: usize, context: &Context) -> Result<Vector<T>> {
+        context.cl_queue()?;
+        let mut res = unsafe { Vector::try_uninitialized_in(size, context)? };
+
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(context.cl_queue()?).ewait(&res.write_wait_list()).enew(&mut event).gws(res.len()).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            res.set_event(event);
+        }
+        Ok(res)
+    }
+
+    /// Create vector with the n elements start, start + step, start + 2 * step...
+    pub fn arange(start: T, step: T, n: usize) -> Vector<T>
+        where T: Add<T, Output=T> + Mul<T, Output=T>
+    {
+        Vector::arange_in(start, step, n, &Context::default())
+    }
+
+    /// Same as arange() but creates the vector in the specified context
+    pub fn arange_in(start: T, step: T, n: usize, context: &Context) -> Vector<T>
+        where T: Add<T, Output=T> + Mul<T, Output=T>
+    {
+        Vector::try_arange_in(start, step, n, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as arange_in() but returns an error if the buffer could not be allocated
+    pub fn try_arange_in(start: T, step: T, n: usize, context: &Context) -> Result<Vector<T>>
+        where T: Add<T, Output=T> + Mul<T, Output=T>
+    {
+        Vector::generate_builtin(n, context, |idx| start + T::from_f64(idx as f64) * step, |k| {
+            let kernel = &mut k.arange;
+            kernel.set_arg_scl_named("start", start)?;
+            kernel.set_arg_scl_named("step", step)?;
+            Ok(kernel)
+        })
+    }
+
+    /// Create vector with n evenly spaced elements from start to stop, both included
+    pub fn linspace(start: T, stop: T, n: usize) -> Vector<T>
+        where T: Real
+    {
+        Vector::linspace_in(start, stop, n, &Context::default())
+    }
+
+    /// Same as linspace() but creates the vector in the specified context
+    pub fn linspace_in(start: T, stop: T, n: usize, context: &Context) -> Vector<T>
+        where T: Real
+    {
+        Vector::try_linspace_in(start, stop, n, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as linspace_in() but returns an error if the buffer could not be allocated
+    pub fn try_linspace_in(start: T, stop: T, n: usize, context: &Context) -> Result<Vector<T>>
+        where T: Real
+    {
+        let (step, stop) = linspace_step(start, stop, n);
+        let last = n.saturating_sub(1);
+
+        Vector::generate_builtin(n, context,
+            |idx| if idx == last { stop } else { start + T::from_f64(idx as f64) * step },
+            |k| {
+                let kernel = &mut k.linspace;
+                kernel.set_arg_scl_named("start", start)?;
+                kernel.set_arg_scl_named("step", step)?;
+                kernel.set_arg_scl_named("stop", stop)?;
+                kernel.set_arg_scl_named("last", last as u64)?;
+                Ok(kernel)
+            })
+    }
+
+    /// Create vector with n elements from base^start to base^stop, evenly spaced on a log scale
+    pub fn logspace(start: T, stop: T, n: usize, base: T) -> Vector<T>
+        where T: Real
+    {
+        Vector::logspace_in(start, stop, n, base, &Context::default())
+    }
+
+    /// Same as logspace() but creates the vector in the specified context
+    pub fn logspace_in(start: T, stop: T, n: usize, base: T, context: &Context) -> Vector<T>
+        where T: Real
+    {
+        Vector::try_logspace_in(start, stop, n, base, context).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as logspace_in() but returns an error if the buffer could not be allocated
+    pub fn try_logspace_in(start: T, stop: T, n: usize, base: T, context: &Context) -> Result<Vector<T>>
+        where T: Real
+    {
+        let (step, stop) = linspace_step(start, stop, n);
+        let last = n.saturating_sub(1);
+
+        Vector::generate_builtin(n, context,
+            |idx| base.powf(if idx == last { stop } else { start + T::from_f64(idx as f64) * step }),
+            |k| {
+                let kernel = &mut k.logspace;
+                kernel.set_arg_scl_named("start", start)?;
+                kernel.set_arg_scl_named("step", step)?;
+                kernel.set_arg_scl_named("stop", stop)?;
+                kernel.set_arg_scl_named("last", last as u64)?;
+                kernel.set_arg_scl_named("base", base)?;
+                Ok(kernel)
+            })
+    }
+
+    /// Create a vector of size elements with the kernel returned by `kernel`, `f` computes the
+    /// element at an index instead for the native backend
+    fn generate_builtin<K, F>(size: usize, context: &Context, f: F, kernel: K) -> Result<Vector<T>>
+        where K: FnOnce(&mut Kernels) -> Result<&mut Kernel>, F: Fn(usize) -> T
+    {
+        if context.is_native() {
+            return Ok(Vector::from_host((0..size).map(f).collect(), context));
+        }
+
+        let mut res = unsafe { Vector::try_uninitialized_in(size, context)? };
+        if size == 0 {
+            return Ok(res);
+        }
+
+        let mut kernels = context.kernels::<T>()?;
+        let kernel = kernel(&mut kernels)?;
+
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(context.cl_queue()?).ewait(&res.write_wait_list()).enew(&mut event).gws(size).enq()?;
+            context.profile_kernel(kernel, &event);
+            res.set_event(event);
+        }
+        Ok(res)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn from_for_each2_lock_free(a: &Vector<T>, b: &Vector<T>, kernel: &mut Kernel) -> Vector<T> {
+        Vector::try_from_for_each2_lock_free(a, b, kernel).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as from_for_each2_lock_free() but returns an error instead of panicking
+    pub fn try_from_for_each2_lock_free(a: &Vector<T>, b: &Vector<T>, kernel: &mut Kernel) -> Result<Vector<T>> {
+        a.context.check_same(&b.context)?;
+        check_len(a.len(), b.len())?;
+
+        let mut res = unsafe{ Vector::try_uninitialized_in(a.len(), &a.context)? };
+
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        kernel.set_arg_buf_named("A", Some(a.buf()))?;
+        kernel.set_arg_buf_named("B", Some(b.buf()))?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(res.context.cl_queue()?).ewait(&Vector::wait_list(&[a, b], &res)).enew(&mut event).gws(res.len()).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[a, b], &event);
+            res.set_event(event);
+        }
+
+        Ok(res)
+    }
+
+    pub fn for_each_mut(&mut self, other: &Vector<T>, kernel: &mut Kernel){
+        self.try_for_each_mut(other, kernel).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as for_each_mut() but returns an error instead of panicking
+    pub fn try_for_each_mut(&mut self, other: &Vector<T>, kernel: &mut Kernel) -> Result<()> {
+        self.context.check_same(&other.context)?;
+        check_len(self.len(), other.len())?;
+
+        kernel.set_arg_buf_named("C", Some(self.buf_mut()))?;
+        kernel.set_arg_buf_named("B", Some(other.buf()))?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(self.context.cl_queue()?).ewait(&Vector::wait_list(&[other], self)).enew(&mut event).gws(self.len()).enq()?;
+            self.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[other], &event);
+            self.set_event(event);
+        }
+        Ok(())
+    }
+
+    /// Run kernel for every element in vector, the kernel has to take the vector as argument C
+    ///
+    /// See map() for elementwise math without writing a kernel.
+    pub fn map_mut(&mut self, kernel: &mut Kernel) {
+        self.try_map_mut(kernel).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as map_mut() but returns an error instead of panicking, fails with Error::NoDevice
+    /// for vectors stored on the host by the native backend
+    pub fn try_map_mut(&mut self, kernel: &mut Kernel) -> Result<()> {
+        if self.context.is_native() {
+            return Err(requires_opencl());
+        }
+        kernel.set_arg_buf_named("C", Some(self.buf_mut()))?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(self.context.cl_queue()?).ewait(&self.write_wait_list()).enew(&mut event).gws(self.len()).enq()?;
+            self.context.profile_kernel(kernel, &event);
+            self.set_event(event);
+        }
+        Ok(())
+    }
+
+    /// Returns the result of kernel run for every element, the kernel has to take the result as
+    /// argument C and self as argument B
+    ///
+    /// See map() for elementwise math without writing a kernel.
+    pub fn map_lock_free(&self, kernel: &mut Kernel) -> Vector<T> {
+        self.try_map_lock_free(kernel).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as map_lock_free() but returns an error instead of panicking, see try_map_mut()
+    pub fn try_map_lock_free(&self, kernel: &mut Kernel) -> Result<Vector<T>> {
+        if self.context.is_native() {
+            return Err(requires_opencl());
+        }
+        let mut res = unsafe{ Vector::try_uninitialized_in(self.len(), &self.context)? };
+
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        kernel.set_arg_buf_named("B", Some(self.buf()))?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(res.context.cl_queue()?).ewait(&Vector::wait_list(&[self], &res)).enew(&mut event).gws(res.len()).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[self], &event);
+            res.set_event(event);
+        }
+        Ok(res)
+    }
+
+    /// Compute the OpenCL C expression expr for every element x of self, e.g. "x * x + 1"
+    ///
+    /// The element index is available as i and OpenCL built in functions like sqrt() can be used.
+    /// The kernel is compiled on first use and cached by type and expression. The native backend
+    /// only supports + - * /, parentheses and numbers, it fails with Error::Expression otherwise.
+    /// Expressions for integer types fail with Error::Expression on literals like 1.5 or 1e3.
+    ///
+    /// Every distinct expr compiles a kernel of its own, only a limited number is cached per
+    /// context. Values that change between calls should be passed as scalars of an Expr, e.g.
+    /// `&v * s`, instead of being formatted into expr.
+    pub fn map(&self, expr: &str) -> Vector<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        self.try_map(expr).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-use cl_data;
-use get_kernels;
+    /// Same as map() but returns an error instead of panicking, fails with Error::Build if expr
+    /// does not compile
+    pub fn try_map(&self, expr: &str) -> Result<Vector<T>>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        expr::eval_str(&[self], &["x"], expr)
+    }
 
-pub struct Vector<T>
-    where T: Parameter
-{
-    pub(crate) data: Buffer<T>
-}
+    /// Compute expr for every pair of elements a of self and b of other, e.g. "a * b - 1", see
+    /// map()
+    pub fn zip_with(&self, other: &Vector<T>, expr: &str) -> Vector<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        self.try_zip_with(other, expr).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-impl<T: Parameter> Vector<T> {
-    pub fn new(default_val: T, size: usize) -> Vector<T>
-        where T: Clone
+    /// Same as zip_with() but returns an error instead of panicking
+    pub fn try_zip_with(&self, other: &Vector<T>, expr: &str) -> Result<Vector<T>>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
     {
-        Vector::from_vec(vec![default_val; size])
+        expr::eval_str(&[self, other], &["a", "b"], expr)
     }
 
-    pub unsafe fn uninitialized_lock_free(size: usize, queue: ocl::Queue) -> Vector<T> {
-        let buff = Buffer::builder()
-            .queue(queue)
-            .flags(MemFlags::new().read_write())
-            .dims(size)
-            .build().unwrap();
-        
-        Vector {
-            data: buff
+    /// Compute expr for the elements of up to 8 vectors named a, b, c... in the order of
+    /// inputs, e.g. "a * b - c", see map()
+    ///
+    /// The result is in the stream of the first input.
+    pub fn zip_n(inputs: &[&Vector<T>], expr: &str) -> Vector<T>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        Vector::try_zip_n(inputs, expr).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as zip_n() but returns an error instead of panicking, fails with
+    /// Error::Expression if there are no inputs or more than 8
+    pub fn try_zip_n(inputs: &[&Vector<T>], expr: &str) -> Result<Vector<T>>
+        where T: Add<T, Output=T> + Sub<T, Output=T> + Mul<T, Output=T> + Div<T, Output=T>
+    {
+        if inputs.is_empty() || inputs.len() > expr::INPUT_NAMES.len() {
+            return Err(Error::Expression(format!(
+                "{} inputs, expected 1 to {}", inputs.len(), expr::INPUT_NAMES.len())));
         }
+        expr::eval_str(inputs, &expr::INPUT_NAMES[..inputs.len()], expr)
     }
 
-    pub unsafe fn uninitialized(size: usize) -> Vector<T> {
-        let queue = get_kernels::<T>(T::type_to_str()).queue.clone();
-        Vector::<T>::uninitialized_lock_free(size, queue)
+    /// Overwrite all elements with uniform random numbers, floats between low and high and
+    /// integers in the range low..high, see random::Rng
+    pub fn fill_uniform(&mut self, rng: &mut Rng, low: T, high: T)
+        where T: Random
+    {
+        self.try_fill_uniform(rng, low, high).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn from_vec(v: Vec<T>) -> Vector<T> {
-        let queue = cl_data::<T>().queue.queue().clone();
-        Vector::<T>::from_vec_lock_free(v, queue)
+    /// Same as fill_uniform() but returns an error instead of panicking, fails with
+    /// Error::InvalidArgument if the integer range is empty
+    pub fn try_fill_uniform(&mut self, rng: &mut Rng, low: T, high: T) -> Result<()>
+        where T: Random
+    {
+        let range = T::uniform_range(low, high)?;
+        let (seed, stream) = (rng.seed(), rng.next_stream());
+
+        self.fill_random(
+            |c| random::fill_uniform(c, seed, stream, low, high, range),
+            |k| {
+                let kernel = &mut k.rng_uniform;
+                kernel.set_arg_scl_named("low", low)?;
+                kernel.set_arg_scl_named("high", high)?;
+                kernel.set_arg_scl_named("range", range)?;
+                Ok(kernel)
+            },
+            seed, stream)
     }
 
-    pub(crate) fn from_vec_lock_free(v: Vec<T>, queue: ocl::Queue) -> Vector<T> {
-        let buff = Buffer::builder()
-            .queue(queue)
-            .flags(MemFlags::new().read_write().copy_host_ptr())
-            .dims(v.len())
-            .host_data(&v)
-            .build().unwrap();
+    /// Overwrite all elements with normal distributed random numbers, see random::Rng
+    pub fn fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T)
+        where T: RandomNormal
+    {
+        self.try_fill_normal(rng, mean, std_dev).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        Vector {
-            data: buff
-        }
+    /// Same as fill_normal() but returns an error instead of panicking
+    pub fn try_fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T) -> Result<()>
+        where T: RandomNormal
+    {
+        let (seed, stream) = (rng.seed(), rng.next_stream());
+
+        self.fill_random(
+            |c| random::fill_normal(c, seed, stream, mean, std_dev),
+            |k| {
+                let kernel = &mut k.rng_normal;
+                kernel.set_arg_scl_named("mean", mean)?;
+                kernel.set_arg_scl_named("std_dev", std_dev)?;
+                Ok(kernel)
+            },
+            seed, stream)
     }
 
-    pub fn to_vec(&self) -> Vec<T> {
-        let mut res = Vec::with_capacity(self.len());
-        unsafe {
-            res.set_len(self.len())     // TODO: remove this ugliness
-        }
-        self.data.read(&mut res).enq().unwrap();
-        res
+    /// Overwrite every element with one with probability p and zero otherwise, see random::Rng
+    pub fn fill_bernoulli(&mut self, rng: &mut Rng, p: f64) {
+        self.try_fill_bernoulli(rng, p).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn generate(kernel: &mut Kernel, size: usize) -> Vector<T> {
-        let mut res = unsafe { Vector::uninitialized(size) };
+    /// Same as fill_bernoulli() but returns an error instead of panicking, fails with
+    /// Error::InvalidArgument if p is not in [0, 1]
+    pub fn try_fill_bernoulli(&mut self, rng: &mut Rng, p: f64) -> Result<()> {
+        let threshold = random::bernoulli_threshold(p)?;
+        let (seed, stream) = (rng.seed(), rng.next_stream());
+
+        self.fill_random(
+            |c| random::fill_bernoulli(c, seed, stream, threshold),
+            |k| {
+                let kernel = &mut k.rng_bernoulli;
+                kernel.set_arg_scl_named("threshold", threshold)?;
+                Ok(kernel)
+            },
+            seed, stream)
+    }
+
+    /// Run the random kernel returned by `kernel` with its distribution arguments set, `f` is
+    /// used instead by the native backend
+    fn fill_random<K, F>(&mut self, f: F, kernel: K, seed: u64, stream: u64) -> Result<()>
+        where K: FnOnce(&mut Kernels) -> Result<&mut Kernel>, F: FnOnce(&mut [T])
+    {
+        if let Data::Host(ref mut c) = self.data {
+            f(c);
+            return Ok(());
+        }
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let context = self.context.clone();
+        let mut kernels = context.kernels::<T>()?;
+        let kernel = kernel(&mut kernels)?;
+
+        kernel.set_arg_buf_named("C", Some(self.buf_mut()))?;
+        kernel.set_arg_scl_named("seed", seed)?;
+        kernel.set_arg_scl_named("stream", stream)?;
 
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
         unsafe {
             let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
+            kernel.cmd().queue(self.context.cl_queue()?).ewait(&self.write_wait_list()).enew(&mut event).gws(self.len()).enq()?;
+            self.context.profile_kernel(kernel, &event);
+            self.set_event(event);
         }
-        res
+        Ok(())
     }
 
-    pub fn len(&self) -> usize {
-        self.data.len()
+    /// Apply `kernel` elementwise on self and other, `f` is used instead by the native backend
+    fn zip_op<K, F>(&self, other: &Vector<T>, kernel: K, f: F) -> Result<Vector<T>>
+        where K: FnOnce(&mut Kernels) -> &mut Kernel, F: Fn(T, T) -> T
+    {
+        self.context.check_same(&other.context)?;
+        if let (Data::Host(a), Data::Host(b)) = (&self.data, &other.data) {
+            check_len(a.len(), b.len())?;
+            return Ok(Vector::from_host(native::zip(a, b, f), &self.context));
+        }
+
+        let mut kernels = self.context.kernels::<T>()?;
+        Vector::try_from_for_each2_lock_free(self, other, kernel(&mut kernels))
+    }
+
+    /// Apply `kernel` elementwise on self and other storing the result in self, `f` is used
+    /// instead by the native backend
+    fn zip_assign<K, F>(&mut self, other: &Vector<T>, kernel: K, f: F) -> Result<()>
+        where K: FnOnce(&mut Kernels) -> &mut Kernel, F: Fn(&mut T, T)
+    {
+        self.context.check_same(&other.context)?;
+        if let (Data::Host(c), Data::Host(b)) = (&mut self.data, &other.data) {
+            check_len(c.len(), b.len())?;
+            native::zip_assign(c, b, f);
+            return Ok(());
+        }
+
+        let context = self.context.clone();
+        let mut kernels = context.kernels::<T>()?;
+        self.try_for_each_mut(other, kernel(&mut kernels))
     }
 
-    pub fn from_for_each2_lock_free(a: &Vector<T>, b: &Vector<T>, kernel: &mut Kernel, queue: ocl::Queue) -> Vector<T> {
-        assert_eq!(a.len(), b.len());
+    /// Apply `kernel` with the scalar on every element, `f` is used instead by the native backend
+    fn map_scl<K, F>(&self, scalar: T, kernel: K, f: F) -> Result<Vector<T>>
+        where K: FnOnce(&mut Kernels) -> &mut Kernel, F: Fn(T) -> T
+    {
+        if let Data::Host(ref a) = self.data {
+            return Ok(Vector::from_host(native::map(a, f), &self.context));
+        }
+
+        let mut kernels = self.context.kernels::<T>()?;
+        let kernel = kernel(&mut kernels);
 
-        let mut res = unsafe{ Vector::uninitialized_lock_free(a.len(), queue) };
+        let mut res = unsafe{ Vector::try_uninitialized_in(
+            self.len(),
+            &self.context
+        )? };
 
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-        kernel.set_arg_buf_named("A", Some(&a.data)).unwrap();
-        kernel.set_arg_buf_named("B", Some(&b.data)).unwrap();
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        kernel.set_arg_buf_named("A", Some(self.buf()))?;
+        kernel.set_arg_scl_named("B", scalar)?;
 
         unsafe {
             let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
+            kernel.cmd().queue(res.context.cl_queue()?).ewait(&Vector::wait_list(&[self], &res)).enew(&mut event).gws(res.len()).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[self], &event);
+            res.set_event(event);
         }
-
-        res
+        Ok(res)
     }
 
-    pub fn for_each_mut(&mut self, other: &Vector<T>, kernel: &mut Kernel){
-        assert_eq!(self.len(), other.len());
+    /// Apply `kernel` with the scalar on every element in place, `f` is used instead by the
+    /// native backend
+    fn map_assign_scl<K, F>(&mut self, scalar: T, kernel: K, f: F) -> Result<()>
+        where K: FnOnce(&mut Kernels) -> &mut Kernel, F: Fn(&mut T)
+    {
+        if let Data::Host(ref mut c) = self.data {
+            native::map_assign(c, f);
+            return Ok(());
+        }
 
-        kernel.set_arg_buf_named("C", Some(&mut self.data)).unwrap();
-        kernel.set_arg_buf_named("B", Some(&other.data)).unwrap();
+        let context = self.context.clone();
+        let mut kernels = context.kernels::<T>()?;
+        let kernel = kernel(&mut kernels);
+
+        kernel.set_arg_buf_named("C", Some(self.buf_mut()))?;
+        kernel.set_arg_scl_named("B", scalar)?;
 
         unsafe {
             let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(self.len()).enq().unwrap();
-            event.wait_for().unwrap();
+            kernel.cmd().queue(self.context.cl_queue()?).ewait(&self.write_wait_list()).enew(&mut event).gws(self.len()).enq()?;
+            self.context.profile_kernel(kernel, &event);
+            self.set_event(event);
         }
+        Ok(())
+    }
+
+    /// Same as `self + other` but returns an error instead of panicking
+    pub fn try_add(&self, other: &Vector<T>) -> Result<Vector<T>>
+        where T: Add<T, Output=T>
+    {
+        self.zip_op(other, |k| &mut k.add_vec_vec, |a, b| a + b)
+    }
+
+    /// Same as `self - other` but returns an error instead of panicking
+    pub fn try_sub(&self, other: &Vector<T>) -> Result<Vector<T>>
+        where T: Sub<T, Output=T>
+    {
+        self.zip_op(other, |k| &mut k.sub_vec_vec, |a, b| a - b)
+    }
+
+    /// Same as `self * other` but returns an error instead of panicking
+    pub fn try_mul(&self, other: &Vector<T>) -> Result<Vector<T>>
+        where T: Mul<T, Output=T>
+    {
+        self.zip_op(other, |k| &mut k.mul_vec_vec, |a, b| a * b)
     }
 
-    /// Applies p for every element in vector
-    pub fn map_mut<F: FnMut(&mut T)>(&mut self, kernel: &mut Kernel) {
-        kernel.set_arg_buf_named("C", Some(&mut self.data)).unwrap();
+    /// Same as `self / other` but returns an error instead of panicking
+    pub fn try_div(&self, other: &Vector<T>) -> Result<Vector<T>>
+        where T: Div<T, Output=T>
+    {
+        self.zip_op(other, |k| &mut k.div_vec_vec, |a, b| a / b)
+    }
+
+    /// Same as `self += other` but returns an error instead of panicking
+    pub fn try_add_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: AddAssign<T>
+    {
+        self.zip_assign(other, |k| &mut k.add_assign_vec_vec, |c, b| *c += b)
+    }
+
+    /// Same as `self -= other` but returns an error instead of panicking
+    pub fn try_sub_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: SubAssign<T>
+    {
+        self.zip_assign(other, |k| &mut k.sub_assign_vec_vec, |c, b| *c -= b)
+    }
+
+    /// Same as `self *= other` but returns an error instead of panicking
+    pub fn try_mul_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: MulAssign<T>
+    {
+        self.zip_assign(other, |k| &mut k.mul_assign_vec_vec, |c, b| *c *= b)
+    }
+
+    /// Same as `self /= other` but returns an error instead of panicking
+    pub fn try_div_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: DivAssign<T>
+    {
+        self.zip_assign(other, |k| &mut k.div_assign_vec_vec, |c, b| *c /= b)
+    }
+
+    /// Same as `self * scalar` but returns an error instead of panicking
+    pub fn try_mul_scl(&self, scalar: T) -> Result<Vector<T>>
+        where T: Mul<T, Output=T>
+    {
+        self.map_scl(scalar, |k| &mut k.mul_vec_scl, |a| a * scalar)
+    }
+
+    /// Same as `self / scalar` but returns an error instead of panicking
+    pub fn try_div_scl(&self, scalar: T) -> Result<Vector<T>>
+        where T: Div<T, Output=T>
+    {
+        self.map_scl(scalar, |k| &mut k.div_vec_scl, |a| a / scalar)
+    }
+
+    /// Same as `self *= scalar` but returns an error instead of panicking
+    pub fn try_mul_assign_scl(&mut self, scalar: T) -> Result<()>
+        where T: MulAssign<T>
+    {
+        self.map_assign_scl(scalar, |k| &mut k.mul_assign_vec_scl, |c| *c *= scalar)
+    }
+
+    /// Same as `self /= scalar` but returns an error instead of panicking
+    pub fn try_div_assign_scl(&mut self, scalar: T) -> Result<()>
+        where T: DivAssign<T>
+    {
+        self.map_assign_scl(scalar, |k| &mut k.div_assign_vec_scl, |c| *c /= scalar)
+    }
+
+    /// Same as `self * other_m` but returns an error instead of panicking
+    pub fn try_mul_mat(&self, other_m: &Matrix<T>) -> Result<Vector<T>>
+        where T: Mul<T, Output=T> + Add<T, Output=T>
+    {
+        self.context.check_same(other_m.context())?;
+        check_len(self.len(), other_m.get_row_count())?;
+
+        if let (Data::Host(a), Data::Host(b)) = (&self.data, &other_m.data.data) {
+            return Ok(Vector::from_host(native::mul_vec_mat(a, b, other_m.get_col_count()), &self.context));
+        }
+
+        let mut kernels = self.context.kernels::<T>()?;
+        let mut res = unsafe{ Vector::try_uninitialized_in(
+            other_m.get_col_count(),
+            &self.context)?
+        };
+
+        let kernel = &mut kernels.mul_vec_mat;
+
+        kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+        kernel.set_arg_buf_named("A", Some(self.buf()))?;
+        kernel.set_arg_buf_named("B", Some(other_m.data.buf()))?;
+
+        kernel.set_arg_scl_named::<i32>("B_col_count", other_m.get_col_count() as i32)?;
+        kernel.set_arg_scl_named::<i32>("A_len", self.len() as i32)?;
 
         unsafe {
             let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(self.len()).enq().unwrap();
-            event.wait_for().unwrap();
+            kernel.cmd().queue(res.context.cl_queue()?).ewait(&Vector::wait_list(&[self, &other_m.data], &res)).enew(&mut event).gws(res.len()).enq()?;
+            res.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[self, &other_m.data], &event);
+            res.set_event(event);
         }
+
+        Ok(res)
     }
 
-    /// Returns copy of self with p applied to each element
-    pub fn map_lock_free<F: FnMut(&T)->T >(&self, kernel: &mut Kernel, queue: ocl::Queue) -> Vector<T> {
-        let mut res = unsafe{ Vector::uninitialized_lock_free(self.len(), queue) };
+    /// Same as `self == other` but returns an error instead of panicking
+    pub fn try_eq(&self, other: &Vector<T>) -> Result<bool> {
+        self.context.check_same(&other.context)?;
+        if self.len() != other.len() {
+            return Ok(false);
+        }
+
+        if let (Data::Host(a), Data::Host(b)) = (&self.data, &other.data) {
+            return Ok(native::eq_vec(a, b));
+        }
+
+        let mut kernels = self.context.kernels::<T>()?;
+        let kernel = &mut kernels.eq_vec;
+
+        let mut is_equal = Vector::try_from_vec_in(
+            vec![1u8],
+            &self.context,
+        )?;
+
+        kernel.set_arg_buf_named("C", Some(is_equal.buf_mut()))?;
+        kernel.set_arg_buf_named("A", Some(self.buf()))?;
+        kernel.set_arg_buf_named("B", Some(other.buf()))?;
+
+        unsafe {
+            let mut event = ocl::Event::empty();
+            kernel.cmd().queue(is_equal.context.cl_queue()?).ewait(&Vector::wait_list(&[self, other], &is_equal)).enew(&mut event).gws(self.len()).enq()?;
+            is_equal.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[self, other], &event);
+            is_equal.set_event(event);
+        }
+
+        Ok(is_equal.try_to_vec()?[0] != 0)
+    }
 
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-        kernel.set_arg_buf_named("B", Some(&self.data)).unwrap();
+    /// Same as clone() but returns an error instead of panicking
+    pub fn try_clone(&self) -> Result<Vector<T>> {
+        if let Data::Host(ref v) = self.data {
+            return Ok(Vector::from_host(v.clone(), &self.context));
+        }
 
         unsafe {
+            let mut result = Self::try_uninitialized_in(self.len(), &self.context)?;
             let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
+            let wait_list = Vector::wait_list(&[self], &result);
+            self.buf().copy(result.buf_mut(), None, Some(self.len())).queue(self.context.cl_queue()?).ewait(&wait_list).enew(&mut event).enq()?;
+            result.context.profile_transfer::<T>("copy", &event);
+            Vector::add_read_event(&[self], &event);
+            result.set_event(event);
+            Ok(result)
         }
-        res
     }
 
+    /// OpenCL buffer of vector, commands using it have to wait for events() first
+    ///
+    /// The buffer may be longer than the vector, only its first len() elements belong to it.
+    /// The buffer is not reused for other vectors once the vector is dropped since it may have
+    /// been cloned.
+    ///
+    /// # Safety
+    ///
+    /// Commands using the buffer bypass the event tracking of the vector, they have to wait for
+    /// events() first and the vector must not be used before they completed.
     pub unsafe fn get_buffer(&self) -> &ocl::Buffer<T> {
-        &self.data
+        self.context.disown_buffer(self.buf());
+        self.buf()
     }
 
+    /// Same as get_buffer()
+    ///
+    /// # Safety
+    ///
+    /// See get_buffer().
     pub unsafe fn get_buffer_mut(&mut self) -> &mut ocl::Buffer<T> {
-        &mut self.data
+        self.context.disown_buffer(self.buf());
+        self.buf_mut()
     }
 
-    pub(crate) unsafe fn read_file_only_data(file: &mut ::std::fs::File, elem_count: u64) -> Result<Vector<T>, ::std::io::Error> {
+    pub(crate) unsafe fn read_file_only_data(file: &mut ::std::fs::File, elem_count: u64) -> Result<Vector<T>> {
         use std::io::Read;
 
         let mut data = Vec::with_capacity(elem_count as usize);
@@ -177,20 +1332,20 @@ impl<T: Parameter> Vector<T> {
             data.push(elem);
             raw.clear();
         }
-        Ok(Vector::from_vec(data))
+        Vector::try_from_vec(data)
     }
 
-    pub(crate) fn write_file_only_data(&self, file: &mut ::std::fs::File) -> Result<(), ::std::io::Error> {
+    pub(crate) fn write_file_only_data(&self, file: &mut ::std::fs::File) -> Result<()> {
         use std::slice;
         use std::io::Write;
 
-        let data = self.to_vec();
+        let data = self.try_to_vec()?;
         unsafe {
             let data: &[u8] = slice::from_raw_parts(
                 data.as_ptr() as *const u8,
                 data.len() * ::std::mem::size_of::<T>(),
             );
-            file.write_all(data)
+            Ok(file.write_all(data)?)
         }
     }
 
@@ -200,6 +1355,11 @@ impl<T: Parameter> Vector<T> {
     /// Two u64 will be placed first, representing the number of bytes per element and the
     /// number of elements respectively.
     pub fn write_to_file(&self, file: &mut ::std::fs::File) -> ::std::result::Result<(), ::std::io::Error> {
+        self.try_write_to_file(file).map_err(into_io_error)
+    }
+
+    /// Same as write_to_file() but returns the crate's Error type
+    pub fn try_write_to_file(&self, file: &mut ::std::fs::File) -> Result<()> {
         write_u64(file, ::std::mem::size_of::<T>() as u64)?;     //Store element size in bytes
         write_u64(file, self.len() as u64)?;                     //Store element count
 
@@ -208,16 +1368,23 @@ impl<T: Parameter> Vector<T> {
 
     /// Read data to from file.
     /// NOTE! Buffer will be interpreted in the current systems endianness
-    pub unsafe fn read_from_file(file: &mut ::std::fs::File) -> Result<Vector<T>, ::std::io::Error> {
-        let elem_size = read_u64(file)?;
-
+    ///
+    /// # Safety
+    ///
+    /// The bytes in the file are reinterpreted as elements of T, the file has to be written by
+    /// write_to_file() for the same type.
+    pub unsafe fn read_from_file(file: &mut ::std::fs::File) -> ::std::result::Result<Vector<T>, ::std::io::Error> {
+        Self::try_read_from_file(file).map_err(into_io_error)
+    }
 
-        if (elem_size as usize) != ::std::mem::size_of::<T>() {
-            panic!(
-                "Elem size from buffer does not seem to match, what was expected!\
-                 Missmatch in endiannes?"
-            );
-        }
+    /// Same as read_from_file() but returns an error instead of panicking if the element size
+    /// in the file does not match
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn try_read_from_file(file: &mut ::std::fs::File) -> Result<Vector<T>> {
+        check_elem_size::<T>(read_u64(file)?)?;
 
         let elem_count = read_u64(file)?;
 
@@ -226,69 +1393,106 @@ impl<T: Parameter> Vector<T> {
 
     /// Save Vector to specified path.
     /// NOTE! The file will be encoded in the current systems endianness
-    pub fn save(&self, path: &str) -> Result<(), ::std::io::Error> {
+    pub fn save(&self, path: &str) -> ::std::result::Result<(), ::std::io::Error> {
+        self.try_save(path).map_err(into_io_error)
+    }
+
+    /// Same as save() but returns the crate's Error type
+    pub fn try_save(&self, path: &str) -> Result<()> {
         use std::fs::File;
 
         let mut file = File::create(path)?;
 
-        self.write_to_file(&mut file)
+        self.try_write_to_file(&mut file)
     }
 
     /// Open Vector from specified path.
     /// NOTE! The file will be interpreted in the current systems endianness
-    pub unsafe fn open(path: &str) -> Result<Vector<T>, ::std::io::Error> {
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn open(path: &str) -> ::std::result::Result<Vector<T>, ::std::io::Error> {
+        Vector::<T>::try_open(path).map_err(into_io_error)
+    }
+
+    /// Same as open() but returns the crate's Error type
+    ///
+    /// # Safety
+    ///
+    /// See read_from_file().
+    pub unsafe fn try_open(path: &str) -> Result<Vector<T>> {
         use std::fs::File;
 
-        let mut file = File::open(path).expect("Failed to open file");
+        let mut file = File::open(path)?;
 
-        Ok(Vector::<T>::read_from_file(&mut file).expect("Failed to read all data from file"))
+        Vector::<T>::try_read_from_file(&mut file)
     }
 
+    /// Same as get_buffer()
+    ///
+    /// # Safety
+    ///
+    /// See get_buffer().
     pub unsafe fn get_buf(&self) -> &ocl::Buffer<T> {
-        &self.data
+        self.get_buffer()
     }
 
+    /// Same as get_buffer_mut()
+    ///
+    /// # Safety
+    ///
+    /// See get_buffer().
     pub unsafe fn get_buf_mut(&mut self) -> &mut ocl::Buffer<T> {
-        &mut self.data
+        self.get_buffer_mut()
     }
 }
 
 impl<T: Parameter + ::std::iter::Sum<T>> Vector<T> {
     /// Calculate the sum of every element in the vector
     pub fn sum(&self) -> T {
-        let mut data = cl_data::<T>();
+        self.try_sum().unwrap_or_else(|e| panic!("{}", e))
+    }
 
+    /// Same as sum() but returns an error instead of panicking
+    pub fn try_sum(&self) -> Result<T> {
+        if let Data::Host(ref v) = self.data {
+            return Ok(native::sum_vec(v));
+        }
 
-        let kernels = data.kernels.get_mut(T::type_to_str()).unwrap();
-        let queue = kernels.queue.clone();
+        let mut kernels = self.context.kernels::<T>()?;
 
         let kernel = &mut kernels.sum_vec;
-        let kp = ::get_work_sizes(&kernel);
+        let candidates = tune::reduction_candidates(kernel, self.len())?;
 
-        if self.len() <= kp.work_group_count { // No need to reduce?
-            return self.to_vec().into_iter().sum()
+        if candidates.is_empty() { // No need to reduce?
+            return Ok(self.try_to_vec()?.into_iter().sum())
         }
 
-        unsafe {
-            let mut tmp = Vector::uninitialized_lock_free(kp.work_group_count, queue);
+        kernel.set_arg_buf_named("data", Some(self.buf()))?;
+        kernel.set_arg_scl_named("count", self.len() as i32)?;
 
-            kernel.set_arg_buf_named("data", Some(&self.data)).unwrap();
-            kernel.set_arg_buf_named("results", Some(&mut tmp.data)).unwrap();
-            kernel.set_arg_scl_named("count", self.len() as i32).unwrap();
-            kernel.set_arg_unchecked(3, ocl::enums::KernelArg::Local::<T>(&kp.work_group_size))
-                .unwrap();
+        let name = kernel.name();
+        tune::launch(self.context.cl_queue()?, &name, self.len(), &candidates, |kp| unsafe {
+            let mut tmp = Vector::try_uninitialized_in(kp.work_group_count, &self.context)?;
+
+            kernel.set_arg_buf_named("results", Some(tmp.buf_mut()))?;
+            kernel.set_arg_unchecked(3, ocl::enums::KernelArg::Local::<T>(&kp.work_group_size))?;
 
             let mut event = ocl::Event::empty();
             kernel.cmd()
+                .queue(tmp.context.cl_queue()?)
+                .ewait(&Vector::wait_list(&[self], &tmp))
                 .enew(&mut event)
                 .gws(kp.global_work_size)
                 .lws(kp.work_group_size)
-                .enq()
-                .unwrap();
-            event.wait_for().unwrap();
+                .enq()?;
+            tmp.context.profile_kernel(kernel, &event);
+            Vector::add_read_event(&[self], &event);
+            tmp.set_event(event);
 
-            tmp.to_vec().into_iter().sum()
-        }
+            Ok(tmp.try_to_vec()?.into_iter().sum())
+        })
     }
 }
 
@@ -319,74 +1523,145 @@ impl<T> Vector<T>
     /// Return normalized version of self
     pub fn normalized(&self) -> Self {
         let inv_length = T::one() / self.length();
-        self * inv_length
+        self.try_mul_scl(inv_length).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 pub fn dot<T: Parameter + Mul<T, Output=T> + ::std::iter::Sum<T>>(a: &Vector<T>, b: &Vector<T>) -> T {
-    assert_eq!(a.len(), b.len());
+    try_dot(a, b).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as dot() but returns an error instead of panicking
+pub fn try_dot<T: Parameter + Mul<T, Output=T> + ::std::iter::Sum<T>>(a: &Vector<T>, b: &Vector<T>) -> Result<T> {
+    a.context.check_same(&b.context)?;
+    check_len(a.len(), b.len())?;
 
-    let mut data = cl_data::<T>();
+    if let (Data::Host(a), Data::Host(b)) = (&a.data, &b.data) {
+        return Ok(native::dot_vec_vec(a, b));
+    }
 
-    let kernels = data.kernels.get_mut(T::type_to_str()).unwrap();
-    let queue = kernels.queue.clone();
+    let mut kernels = a.context.kernels::<T>()?;
 
     let kernel = &mut kernels.dot_vec_vec;
 
-    let kp = ::get_work_sizes(&kernel);
+    let candidates = tune::reduction_candidates(kernel, a.len())?;
 
-    if a.len() <= kp.work_group_count { // No need to reduce?
-        return a.to_vec().iter().zip(b.to_vec().iter()).map(|(a, b)| *a * *b).sum()
+    if candidates.is_empty() { // No need to reduce?
+        return Ok(a.try_to_vec()?.iter().zip(b.try_to_vec()?.iter()).map(|(a, b)| *a * *b).sum())
     }
 
+    kernel.set_arg_buf_named("a", Some(a.buf()))?;
+    kernel.set_arg_buf_named("b", Some(b.buf()))?;
+    kernel.set_arg_scl_named("count", a.len() as i32)?;
 
-    unsafe {
-        let mut tmp = Vector::uninitialized_lock_free(kp.work_group_count, queue);
+    let name = kernel.name();
+    tune::launch(a.context.cl_queue()?, &name, a.len(), &candidates, |kp| unsafe {
+        let mut tmp = Vector::try_uninitialized_in(kp.work_group_count, &a.context)?;
 
-        kernel.set_arg_buf_named("a", Some(&a.data)).unwrap();
-        kernel.set_arg_buf_named("b", Some(&b.data)).unwrap();
-        kernel.set_arg_buf_named("results", Some(&mut tmp.data)).unwrap();
-        kernel.set_arg_scl_named("count", a.len() as i32).unwrap();
-        kernel.set_arg_unchecked(4, ocl::enums::KernelArg::Local::<T>(&kp.work_group_size))
-            .unwrap();
+        kernel.set_arg_buf_named("results", Some(tmp.buf_mut()))?;
+        kernel.set_arg_unchecked(4, ocl::enums::KernelArg::Local::<T>(&kp.work_group_size))?;
 
         let mut event = ocl::Event::empty();
         kernel.cmd()
+            .queue(tmp.context.cl_queue()?)
+            .ewait(&Vector::wait_list(&[a, b], &tmp))
             .enew(&mut event)
             .gws(kp.global_work_size)
             .lws(kp.work_group_size)
-            .enq()
-            .unwrap();
-        event.wait_for().unwrap();
-        tmp.to_vec().into_iter().sum()
+            .enq()?;
+        tmp.context.profile_kernel(kernel, &event);
+        Vector::add_read_event(&[a, b], &event);
+        tmp.set_event(event);
+        Ok(tmp.try_to_vec()?.into_iter().sum())
+    })
+}
+
+/// Fails with Error::ShapeMismatch if a and b are not the same length
+fn check_len(a: usize, b: usize) -> Result<()> {
+    if a == b {
+        Ok(())
+    } else {
+        Err(Error::shape_mismatch(a, b))
     }
 }
 
+/// Fails with Error::ShapeMismatch if offset..offset + len is not within a vector of vec_len
+/// elements
+pub(crate) fn check_range(offset: usize, len: usize, vec_len: usize) -> Result<()> {
+    if offset.checked_add(len).is_some_and(|end| end <= vec_len) {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch(format!(
+            "range {}..{} out of bounds for {} elements", offset, offset.saturating_add(len), vec_len)))
+    }
+}
 
-impl<'a, 'b, T> Add<&'b Vector<T>> for &'a Vector<T>
-    where T: Copy + Add<T, Output=T> + Parameter
-{
-    type Output = Vector<T>;
-    fn add(self, other: &'b Vector<T>) -> Vector<T> {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let queue = kernels.queue.clone();
-        Vector::from_for_each2_lock_free(
-        self, other,
-        &mut kernels.add_vec_vec,
-            queue
-        )
+/// Offset and length of range within a vector of vec_len elements, fails with
+/// Error::ShapeMismatch if range is out of bounds
+fn resolve_range<R: RangeBounds<usize>>(range: R, vec_len: usize) -> Result<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.saturating_add(1),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => vec_len,
+    };
+    if end < start {
+        return Err(Error::ShapeMismatch(format!("range {}..{} ends before it starts", start, end)));
     }
+    check_range(start, end - start, vec_len)?;
+    Ok((start, end - start))
 }
 
+/// Offsets and lengths of the chunks of chunk_size elements of a vector of vec_len elements
+fn chunk_ranges(vec_len: usize, chunk_size: usize) -> Result<Vec<(usize, usize)>> {
+    if chunk_size == 0 {
+        return Err(Error::ShapeMismatch("chunk size must be greater than 0".to_owned()));
+    }
+    Ok((0..vec_len).step_by(chunk_size).map(|offset| (offset, ::std::cmp::min(chunk_size, vec_len - offset))).collect())
+}
+
+/// Step between the n elements of linspace() and the value of the last element, a single
+/// element is start
+fn linspace_step<T: Real>(start: T, stop: T, n: usize) -> (T, T) {
+    if n > 1 {
+        ((stop - start) / T::from_f64((n - 1) as f64), stop)
+    } else {
+        (T::zero(), start)
+    }
+}
+
+/// Fails with Error::Format if elem_size read from a file does not match the size of T
+pub(crate) fn check_elem_size<T>(elem_size: u64) -> Result<()> {
+    if (elem_size as usize) == ::std::mem::size_of::<T>() {
+        Ok(())
+    } else {
+        Err(Error::Format(format!(
+            "Elem size from buffer does not seem to match, what was expected! \
+             Missmatch in endiannes? ({} bytes vs {} bytes)",
+            elem_size, ::std::mem::size_of::<T>()
+        )))
+    }
+}
+
+/// Unwrap io errors for the functions returning io::Result, all other errors are wrapped in
+/// an io::Error of kind Other
+pub(crate) fn into_io_error(e: Error) -> ::std::io::Error {
+    match e {
+        Error::Io(e) => e,
+        e => ::std::io::Error::other(e.to_string()),
+    }
+}
+
+
 impl<'a, T> AddAssign<&'a Vector<T>> for Vector<T>
     where T: Copy + AddAssign<T> + Parameter
 {
     fn add_assign(&mut self, other: &'a Vector<T>) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        Vector::for_each_mut(
-        self, other,
-        &mut kernels.add_assign_vec_vec
-        );
+        self.try_add_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -400,58 +1675,11 @@ impl<'a, T> Add<&'a Vector<T>> for Vector<T>
     }
 }
 
-impl<'a, 'b, T> Sub<&'b Vector<T>> for &'a Vector<T>
-    where T: Copy + Sub<T, Output=T> + Parameter
-{
-    type Output = Vector<T>;
-    fn sub(self, other: &'b Vector<T>) -> Vector<T> {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let queue = kernels.queue.clone();
-        Vector::from_for_each2_lock_free(
-            self, other,
-            &mut kernels.sub_vec_vec,
-            queue
-        )
-    }
-}
-
 impl<'a, T> SubAssign<&'a Vector<T>> for Vector<T>
     where T: Copy + SubAssign<T> + Parameter
 {
     fn sub_assign(&mut self, other: &'a Vector<T>) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        Vector::for_each_mut(
-            self, other,
-            &mut kernels.sub_assign_vec_vec);
-    }
-}
-
-//Mul by scalar
-impl<'a, 'b, T> Mul<T> for &'a Vector<T>
-    where T: Copy + Mul<T, Output=T> + Parameter
-{
-    type Output = Vector<T>;
-    fn mul(self, scalar: T) -> Vector<T> {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-
-        let queue = kernels.queue.clone();
-        let kernel = &mut kernels.mul_vec_scl;
-
-        let mut res = unsafe{ Vector::uninitialized_lock_free(
-            self.len(),
-            queue
-        )};
-
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-        kernel.set_arg_buf_named("A", Some(&self.data)).unwrap();
-        kernel.set_arg_scl_named("B", scalar).unwrap();
-
-        unsafe {
-            let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
-        }
-        res
+        self.try_sub_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -459,13 +1687,7 @@ impl<T> MulAssign<T> for Vector<T>
     where T: Copy + MulAssign<T> + Parameter
 {
     fn mul_assign(&mut self, scalar: T) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let kernel = &mut kernels.mul_assign_vec_scl;
-
-        kernel.set_arg_buf_named("C", Some(&mut self.data)).unwrap();
-        kernel.set_arg_scl_named("B", scalar).unwrap();
-
-        unsafe { kernel.cmd().gws(self.len()).enq().unwrap(); }
+        self.try_mul_assign_scl(scalar).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -479,148 +1701,73 @@ impl<T> Mul<T> for Vector<T>
     }
 }
 
-impl<'a, 'b, T> Mul<&'b Vector<T>> for &'a Vector<T>
-    where T: Copy + Mul<T, Output=T> + Parameter
-{
-    type Output = Vector<T>;
-    fn mul(self, other: &'b Vector<T>) -> Vector<T> {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let queue = kernels.queue.clone();
-        Vector::from_for_each2_lock_free(
-            self,
-            other,
-            &mut kernels.mul_vec_vec,
-            queue
-        )
-    }
-}
-
 impl<'a, T> MulAssign<&'a Vector<T>> for Vector<T>
     where T: Copy + MulAssign<T> + Parameter
 {
     fn mul_assign(&mut self, other: &'a Vector<T>) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        Vector::for_each_mut(self, other, &mut kernels.mul_assign_vec_vec);
+        self.try_mul_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 //Vector * Matrix
-impl<'a, 'b, T> Mul<&'b Matrix<T>> for &'a Vector<T>
-    where T: Copy + Mul<T, Output=T> + Add + Parameter
+impl<'b, T> Mul<&'b Matrix<T>> for &Vector<T>
+    where T: Copy + Mul<T, Output=T> + Add<T, Output=T> + Parameter
 {
     type Output = Vector<T>;
     fn mul(self, other_m: &'b Matrix<T>) -> Vector<T> {//TODO: check me
-        assert_eq!(self.len(), other_m.get_row_count());
-
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let mut res = unsafe{ Vector::uninitialized_lock_free(
-            other_m.get_col_count(),
-            kernels.queue.clone())
-        };
-
-        let kernel = &mut kernels.mul_vec_mat;
-
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-        kernel.set_arg_buf_named("A", Some(&self.data)).unwrap();
-        kernel.set_arg_buf_named("B", Some(&other_m.data.data)).unwrap();
-
-        kernel.set_arg_scl_named::<i32>("B_col_count", other_m.get_col_count() as i32).unwrap();
-        kernel.set_arg_scl_named::<i32>("A_len", self.len() as i32).unwrap();
-
-        unsafe {
-            let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
-        }
-
-
-        res
+        self.try_mul_mat(other_m).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 /// Compute vector * transpose(other_m), where transpose(other_m) is the transpose of matrix other_m
 pub fn mul_transpose_mat<T>(vector: &Vector<T>, other_m: &Matrix<T>) -> Vector<T>
-    where T: Copy + Mul<T, Output=T> + Add + Parameter
+    where T: Copy + Mul<T, Output=T> + Add<T, Output=T> + Parameter
+{
+    try_mul_transpose_mat(vector, other_m).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as mul_transpose_mat() but returns an error instead of panicking
+pub fn try_mul_transpose_mat<T>(vector: &Vector<T>, other_m: &Matrix<T>) -> Result<Vector<T>>
+    where T: Copy + Mul<T, Output=T> + Add<T, Output=T> + Parameter
 {
-    assert_eq!(vector.len(), other_m.get_col_count());
+    vector.context.check_same(other_m.context())?;
+    check_len(vector.len(), other_m.get_col_count())?;
+
+    if let (Data::Host(a), Data::Host(b)) = (&vector.data, &other_m.data.data) {
+        return Ok(Vector::from_host(native::mul_vec_transpose_mat(a, b, other_m.get_col_count()), &vector.context));
+    }
 
-    let mut kernels = get_kernels::<T>(T::type_to_str());
-    let mut res = unsafe { Vector::uninitialized_lock_free(
+    let mut kernels = vector.context.kernels::<T>()?;
+    let mut res = unsafe { Vector::try_uninitialized_in(
         other_m.get_row_count(),
-        kernels.queue.clone()
-    )};
+        &vector.context
+    )? };
 
 
     let kernel = &mut kernels.mul_vec_transpose_mat;
 
-    kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-    kernel.set_arg_buf_named("A", Some(&vector.data)).unwrap();
-    kernel.set_arg_buf_named("B", Some(&other_m.data.data)).unwrap();
+    kernel.set_arg_buf_named("C", Some(res.buf_mut()))?;
+    kernel.set_arg_buf_named("A", Some(vector.buf()))?;
+    kernel.set_arg_buf_named("B", Some(other_m.data.buf()))?;
 
-    kernel.set_arg_scl_named::<i32>("B_col_count", other_m.get_col_count() as i32).unwrap();
-    kernel.set_arg_scl_named::<i32>("A_len", vector.len() as i32).unwrap();
+    kernel.set_arg_scl_named::<i32>("B_col_count", other_m.get_col_count() as i32)?;
+    kernel.set_arg_scl_named::<i32>("A_len", vector.len() as i32)?;
 
     unsafe {
         let mut event = ocl::Event::empty();
-        kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-        event.wait_for().unwrap();
-    }
-    res
-}
-
-impl<'a, 'b, T> Div<&'b Vector<T>> for &'a Vector<T>
-    where T: Copy + Div<T, Output=T> + Parameter
-{
-    type Output = Vector<T>;
-    fn div(self, other: &'b Vector<T>) -> Vector<T> {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let queue = kernels.queue.clone();
-
-        Vector::from_for_each2_lock_free(
-            self,
-            other,
-            &mut kernels.div_vec_vec,
-            queue
-        )
+        kernel.cmd().queue(res.context.cl_queue()?).ewait(&Vector::wait_list(&[vector, &other_m.data], &res)).enew(&mut event).gws(res.len()).enq()?;
+        res.context.profile_kernel(kernel, &event);
+        Vector::add_read_event(&[vector, &other_m.data], &event);
+        res.set_event(event);
     }
+    Ok(res)
 }
 
 impl<'a, T> DivAssign<&'a Vector<T>> for Vector<T>
     where T: Copy + DivAssign<T> + Parameter
 {
     fn div_assign(&mut self, other: &'a Vector<T>) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        Vector::for_each_mut(self, other, &mut kernels.div_assign_vec_vec);
-    }
-}
-
-//Div by scalar
-impl<'a, T> Div<T> for &'a Vector<T>
-    where T: Parameter + Copy + Div<T, Output=T>
-{
-    type Output = Vector<T>;
-    fn div(self, scalar: T) -> Vector<T> {
-
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let queue = kernels.queue.clone();
-        let kernel = &mut kernels.div_vec_scl;
-
-        let mut res = unsafe{ Vector::uninitialized_lock_free(
-            self.len(),
-            queue
-        )};
-
-        kernel.set_arg_buf_named("C", Some(&mut res.data)).unwrap();
-        kernel.set_arg_buf_named("A", Some(&self.data)).unwrap();
-        kernel.set_arg_scl_named("B", scalar).unwrap();
-
-        unsafe {
-            let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(res.len()).enq().unwrap();
-            event.wait_for().unwrap();
-        }
-
-        res
+        self.try_div_assign(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -628,49 +1775,15 @@ impl<T> DivAssign<T> for Vector<T>
     where T: Copy + DivAssign<T> + Parameter
 {
     fn div_assign(&mut self, scalar: T) {
-        let mut kernels = get_kernels::<T>(T::type_to_str());
-        let kernel = &mut kernels.div_assign_vec_scl;
-
-        kernel.set_arg_buf_named("C", Some(&mut self.data)).unwrap();
-        kernel.set_arg_scl_named("B", scalar).unwrap();
-
-        unsafe {
-            let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(self.len()).enq().unwrap();
-            event.wait_for().unwrap();
-        }
+        self.try_div_assign_scl(scalar).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
-impl<'a, 'b, T> ::std::cmp::PartialEq for Vector<T>
+impl<T> ::std::cmp::PartialEq for Vector<T>
     where T: Copy + ::std::cmp::PartialEq + Parameter
 {
     fn eq(&self, other: &Vector<T>) -> bool {
-        if self.len() != other.len() {
-            return false;
-        }
-
-        let cl_data = cl_data::<T>();
-        let queue = cl_data.queue.queue().clone();
-        let mut kernels = KernelsGuard(cl_data, T::type_to_str());
-        let kernel = &mut kernels.eq_vec;
-
-        let mut is_equal = Vector::from_vec_lock_free(
-            vec![1u8],
-            queue,
-        );
-
-        kernel.set_arg_buf_named("C", Some(&mut is_equal.data)).unwrap();
-        kernel.set_arg_buf_named("A", Some(&self.data)).unwrap();
-        kernel.set_arg_buf_named("B", Some(&other.data)).unwrap();
-
-        unsafe {
-            let mut event = ocl::Event::empty();
-            kernel.cmd().enew(&mut event).gws(self.len()).enq().unwrap();
-            event.wait_for().unwrap();
-        }
-
-        is_equal.to_vec()[0] != 0
+        self.try_eq(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -679,8 +1792,8 @@ impl<T: Parameter + ::std::fmt::Display + Clone> ::std::fmt::Display for Vector<
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result{
         let v = self.to_vec();
         write!(f, "{{ {}", v[0])?;
-        for i in 1..self.len() {
-            write!(f, ", {}", v[i])?;
+        for x in &v[1..] {
+            write!(f, ", {}", x)?;
         }
         write!(f, " }}")
     }
@@ -690,8 +1803,8 @@ impl<T: Parameter + ::std::fmt::Debug + Clone> ::std::fmt::Debug for Vector<T> {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result{
         let v = self.to_vec();
         write!(f, "{{ {:?}", v[0])?;
-        for i in 1..self.len() {
-            write!(f, ", {:?}", v[i])?;
+        for x in &v[1..] {
+            write!(f, ", {:?}", x)?;
         }
         write!(f, " }}")
     }
@@ -699,17 +1812,17 @@ impl<T: Parameter + ::std::fmt::Debug + Clone> ::std::fmt::Debug for Vector<T> {
 
 impl<T: Parameter> Clone for Vector<T> {
     fn clone(&self) -> Self {
-        unsafe {
-            let mut result = Self::uninitialized(self.len());
-            self.data.copy(&mut result.data, None, None).enq().unwrap();
-            result
-        }
+        self.try_clone().unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
-/*
 impl<T: Parameter> Drop for Vector<T> {
+    /// Returns the buffer to the pool of the context for reuse, see Context::set_pool_limit()
     fn drop(&mut self) {
-        println!("Vector dropped");
+        let data = ::std::mem::replace(&mut self.data, Data::Host(Vec::new()));
+        if let Data::Ocl(buff) = data {
+            let events = self.write_wait_list();
+            self.context.recycle_buffer(buff, events);
+        }
     }
-}*/
\ No newline at end of file
+}
\ No newline at end of file
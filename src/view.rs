@@ -0,0 +1,425 @@
+//! Borrowed views of a range of a vector
+//!
+//! Vector::view() and view_mut() borrow a range of a vector without copying it, split_at() and
+//! chunks() partition a whole vector. On OpenCL devices a view is a sub-buffer of the vector's
+//! buffer. Views dereference to Vector for every reading method and operator, mutable views
+//! also have the writing methods and in place operators:
+//!
+//! ```ignore
+//! let mut v = Vector::<f32>::zeros(1024);
+//! {
+//!     let (mut lo, mut hi) = v.split_at_mut(512);
+//!     lo += &b.view(0..512);
+//!     hi *= 2.0;
+//! }
+//! let s = v.view(256..768).sum();
+//! ```
+//!
+//! OpenCL requires the origin of sub-buffers to be aligned to
+//! DeviceProperties::mem_base_addr_align bits and does not allow sub-buffers of sub-buffers.
+//! Views of ranges starting elsewhere and views of views fail with Error::InvalidArgument
+//! instead of silently copying the range. Ranges are copied on the native backend, where a
+//! VectorViewMut writes its copy back when dropped. Empty views have no buffer.
+
+extern crate ocl;
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::sync::Mutex;
+use std::ops::{
+    Deref,
+    Add, AddAssign,
+    SubAssign,
+    Mul, MulAssign,
+    DivAssign
+};
+
+use ocl::Kernel;
+
+use error::{ Error, Result };
+use matrix::Matrix;
+use random::{ Random, RandomNormal, Rng };
+use traits::Parameter;
+use vector::{ check_range, Data, Vector };
+
+/// Read only view of a range of a vector, see Vector::view()
+///
+/// Commands reading the view are tracked by the viewed vector once the view is dropped.
+pub struct VectorView<'a, T: Parameter> {
+    vector: Vector<T>,
+    parent: &'a Vector<T>,
+}
+
+/// Mutable view of a range of a vector, see Vector::view_mut()
+///
+/// Commands writing to the view are tracked by the viewed vector once the view is dropped,
+/// which is why mutable views can not be sent to other threads. Dropping a view never fails,
+/// try_finish() also waits for the commands and reports their errors.
+pub struct VectorViewMut<'a, T: Parameter> {
+    vector: Vector<T>,
+    /// Viewed vector, several views of disjoint ranges may point to it
+    parent: *mut Vector<T>,
+    offset: usize,
+    borrow: PhantomData<&'a mut Vector<T>>,
+}
+
+impl<'a, T: Parameter> VectorView<'a, T> {
+    pub(crate) fn new(parent: &'a Vector<T>, offset: usize, len: usize) -> Result<VectorView<'a, T>> {
+        let vector = sub_vector(parent, offset, len)?;
+        Ok(VectorView { vector, parent })
+    }
+}
+
+impl<'a, T: Parameter> VectorViewMut<'a, T> {
+    /// View of parent, parent has to stay borrowed mutably for 'a and the ranges of all views
+    /// of parent have to be disjoint
+    pub(crate) unsafe fn new(parent: *mut Vector<T>, offset: usize, len: usize) -> Result<VectorViewMut<'a, T>> {
+        let vector = sub_vector(&*parent, offset, len)?;
+        Ok(VectorViewMut {
+            vector,
+            parent,
+            offset,
+            borrow: PhantomData,
+        })
+    }
+}
+
+/// Vector sharing the elements offset..offset + len of v
+///
+/// The range is copied for the native backend and an empty range gets an empty vector without a
+/// buffer. Fails with Error::InvalidArgument if the range can not be a sub-buffer.
+fn sub_vector<T: Parameter>(v: &Vector<T>, offset: usize, len: usize) -> Result<Vector<T>> {
+    check_range(offset, len, v.len())?;
+
+    let buff = match v.data {
+        Data::Host(ref host) => return Ok(Vector::from_host(host[offset..offset + len].to_vec(), &v.context)),
+        Data::Ocl(_) if len == 0 => return Ok(Vector::from_host(Vec::new(), &v.context)),
+        Data::Ocl(ref buff) => buff,
+    };
+
+    if buff.is_sub_buffer() {
+        return Err(Error::InvalidArgument("views of views are not supported".to_owned()));
+    }
+    // The alignment is a power of two
+    let align = v.context.sub_buffer_align()?;
+    if (offset * size_of::<T>()) & (align - 1) != 0 {
+        return Err(Error::InvalidArgument(format!(
+            "view at element {} does not start at a multiple of {} bytes", offset, align)));
+    }
+
+    Ok(Vector {
+        len,
+        data: Data::Ocl(buff.create_sub_buffer(None, offset, len)?),
+        context: v.context.clone(),
+        events: v.events.clone(),
+        readers: Mutex::new(v.readers.lock().unwrap().clone()),
+    })
+}
+
+/// Append the events of src missing in dst
+fn merge_events(dst: &mut ocl::EventList, src: &ocl::EventList) {
+    for e in src.iter() {
+        if !dst.iter().any(|d| d == e) {
+            dst.push(e.clone());
+        }
+    }
+}
+
+impl<'a, T: Parameter> Drop for VectorView<'a, T> {
+    fn drop(&mut self) {
+        if let Data::Ocl(_) = self.vector.data {
+            merge_events(&mut self.parent.readers.lock().unwrap(), &self.vector.readers.lock().unwrap());
+        }
+    }
+}
+
+impl<'a, T: Parameter> Drop for VectorViewMut<'a, T> {
+    fn drop(&mut self) {
+        let parent = unsafe { &mut *self.parent };
+        match self.vector.data {
+            Data::Host(ref v) => if let Data::Host(ref mut p) = parent.data {
+                p[self.offset..self.offset + v.len()].copy_from_slice(v);
+            },
+            Data::Ocl(_) => {
+                merge_events(&mut parent.events, &self.vector.events);
+                merge_events(&mut parent.readers.lock().unwrap(), &self.vector.readers.lock().unwrap());
+            },
+        }
+    }
+}
+
+impl<'a, T: Parameter> Deref for VectorView<'a, T> {
+    type Target = Vector<T>;
+    fn deref(&self) -> &Vector<T> {
+        &self.vector
+    }
+}
+
+impl<'a, T: Parameter> Deref for VectorViewMut<'a, T> {
+    type Target = Vector<T>;
+    fn deref(&self) -> &Vector<T> {
+        &self.vector
+    }
+}
+
+impl<'a, T: Parameter> VectorViewMut<'a, T> {
+    /// End the view, waiting until all commands writing to it have completed
+    pub fn try_finish(self) -> Result<()> {
+        self.vector.try_finish()
+    }
+
+    pub fn write_from(&mut self, src: &[T]) {
+        self.vector.write_from(src)
+    }
+
+    /// Same as Vector::try_write_from()
+    pub fn try_write_from(&mut self, src: &[T]) -> Result<()> {
+        self.vector.try_write_from(src)
+    }
+
+    pub fn write_range_from(&mut self, offset: usize, src: &[T]) {
+        self.vector.write_range_from(offset, src)
+    }
+
+    /// Same as Vector::try_write_range_from()
+    pub fn try_write_range_from(&mut self, offset: usize, src: &[T]) -> Result<()> {
+        self.vector.try_write_range_from(offset, src)
+    }
+
+    pub fn for_each_mut(&mut self, other: &Vector<T>, kernel: &mut Kernel) {
+        self.vector.for_each_mut(other, kernel)
+    }
+
+    /// Same as Vector::try_for_each_mut()
+    pub fn try_for_each_mut(&mut self, other: &Vector<T>, kernel: &mut Kernel) -> Result<()> {
+        self.vector.try_for_each_mut(other, kernel)
+    }
+
+    pub fn map_mut(&mut self, kernel: &mut Kernel) {
+        self.vector.map_mut(kernel)
+    }
+
+    /// Same as Vector::try_map_mut()
+    pub fn try_map_mut(&mut self, kernel: &mut Kernel) -> Result<()> {
+        self.vector.try_map_mut(kernel)
+    }
+
+    pub fn fill_uniform(&mut self, rng: &mut Rng, low: T, high: T)
+        where T: Random
+    {
+        self.vector.fill_uniform(rng, low, high)
+    }
+
+    /// Same as Vector::try_fill_uniform()
+    pub fn try_fill_uniform(&mut self, rng: &mut Rng, low: T, high: T) -> Result<()>
+        where T: Random
+    {
+        self.vector.try_fill_uniform(rng, low, high)
+    }
+
+    pub fn fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T)
+        where T: RandomNormal
+    {
+        self.vector.fill_normal(rng, mean, std_dev)
+    }
+
+    /// Same as Vector::try_fill_normal()
+    pub fn try_fill_normal(&mut self, rng: &mut Rng, mean: T, std_dev: T) -> Result<()>
+        where T: RandomNormal
+    {
+        self.vector.try_fill_normal(rng, mean, std_dev)
+    }
+
+    pub fn fill_bernoulli(&mut self, rng: &mut Rng, p: f64) {
+        self.vector.fill_bernoulli(rng, p)
+    }
+
+    /// Same as Vector::try_fill_bernoulli()
+    pub fn try_fill_bernoulli(&mut self, rng: &mut Rng, p: f64) -> Result<()> {
+        self.vector.try_fill_bernoulli(rng, p)
+    }
+
+    /// Same as `self += other` but returns an error instead of panicking
+    pub fn try_add_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: AddAssign<T>
+    {
+        self.vector.try_add_assign(other)
+    }
+
+    /// Same as `self -= other` but returns an error instead of panicking
+    pub fn try_sub_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: SubAssign<T>
+    {
+        self.vector.try_sub_assign(other)
+    }
+
+    /// Same as `self *= other` but returns an error instead of panicking
+    pub fn try_mul_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: MulAssign<T>
+    {
+        self.vector.try_mul_assign(other)
+    }
+
+    /// Same as `self /= other` but returns an error instead of panicking
+    pub fn try_div_assign(&mut self, other: &Vector<T>) -> Result<()>
+        where T: DivAssign<T>
+    {
+        self.vector.try_div_assign(other)
+    }
+
+    /// Same as `self *= scalar` but returns an error instead of panicking
+    pub fn try_mul_assign_scl(&mut self, scalar: T) -> Result<()>
+        where T: MulAssign<T>
+    {
+        self.vector.try_mul_assign_scl(scalar)
+    }
+
+    /// Same as `self /= scalar` but returns an error instead of panicking
+    pub fn try_div_assign_scl(&mut self, scalar: T) -> Result<()>
+        where T: DivAssign<T>
+    {
+        self.vector.try_div_assign_scl(scalar)
+    }
+}
+
+impl<'a, T: Parameter + fmt::Debug + Clone> fmt::Debug for VectorView<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.vector.fmt(f)
+    }
+}
+
+impl<'a, T: Parameter + fmt::Debug + Clone> fmt::Debug for VectorViewMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.vector.fmt(f)
+    }
+}
+
+/// Vectors and views of vectors, used to implement the operators for every combination
+trait AsVector<T: Parameter> {
+    fn as_vector(&self) -> &Vector<T>;
+}
+
+impl<T: Parameter> AsVector<T> for Vector<T> {
+    fn as_vector(&self) -> &Vector<T> {
+        self
+    }
+}
+
+impl<'a, T: Parameter> AsVector<T> for VectorView<'a, T> {
+    fn as_vector(&self) -> &Vector<T> {
+        &self.vector
+    }
+}
+
+impl<'a, T: Parameter> AsVector<T> for VectorViewMut<'a, T> {
+    fn as_vector(&self) -> &Vector<T> {
+        &self.vector
+    }
+}
+
+/// Vectors and mutable views of vectors
+trait AsVectorMut<T: Parameter> {
+    fn as_vector_mut(&mut self) -> &mut Vector<T>;
+}
+
+impl<T: Parameter> AsVectorMut<T> for Vector<T> {
+    fn as_vector_mut(&mut self) -> &mut Vector<T> {
+        self
+    }
+}
+
+impl<'a, T: Parameter> AsVectorMut<T> for VectorViewMut<'a, T> {
+    fn as_vector_mut(&mut self) -> &mut Vector<T> {
+        &mut self.vector
+    }
+}
+
+macro_rules! impl_view_assign_op {
+    ([$($lt:tt)*] $lhs:ty, $rhs:ty, $tr:ident, $f:ident, $try_f:ident) => {
+        impl<'y, $($lt)* T> $tr<&'y $rhs> for $lhs
+            where T: Copy + $tr<T> + Parameter
+        {
+            fn $f(&mut self, other: &'y $rhs) {
+                self.as_vector_mut().$try_f(other.as_vector()).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+    }
+}
+
+/// Comparisons between two vectors, at least one of them a view, the arithmetic operators are
+/// lazy like those of vectors, see expr::Expr
+macro_rules! impl_view_ops {
+    ($( [$($lt:tt)*] $lhs:ty, $rhs:ty; )+) => { $(
+        impl<$($lt)* T> PartialEq<$rhs> for $lhs
+            where T: Parameter
+        {
+            fn eq(&self, other: &$rhs) -> bool {
+                self.as_vector() == other.as_vector()
+            }
+        }
+    )+ }
+}
+
+impl_view_ops! {
+    ['a,] VectorView<'a, T>, Vector<T>;
+    ['a,] Vector<T>, VectorView<'a, T>;
+    ['a, 'b,] VectorView<'a, T>, VectorView<'b, T>;
+    ['a,] VectorViewMut<'a, T>, Vector<T>;
+    ['a,] Vector<T>, VectorViewMut<'a, T>;
+    ['a, 'b,] VectorViewMut<'a, T>, VectorViewMut<'b, T>;
+    ['a, 'b,] VectorView<'a, T>, VectorViewMut<'b, T>;
+    ['a, 'b,] VectorViewMut<'a, T>, VectorView<'b, T>;
+}
+
+/// In place operators writing to a vector or a mutable view, at least one of them a view
+macro_rules! impl_view_assign_ops {
+    ($( [$($lt:tt)*] $lhs:ty, $rhs:ty; )+) => { $(
+        impl_view_assign_op!([$($lt)*] $lhs, $rhs, AddAssign, add_assign, try_add_assign);
+        impl_view_assign_op!([$($lt)*] $lhs, $rhs, SubAssign, sub_assign, try_sub_assign);
+        impl_view_assign_op!([$($lt)*] $lhs, $rhs, MulAssign, mul_assign, try_mul_assign);
+        impl_view_assign_op!([$($lt)*] $lhs, $rhs, DivAssign, div_assign, try_div_assign);
+    )+ }
+}
+
+impl_view_assign_ops! {
+    ['a,] Vector<T>, VectorView<'a, T>;
+    ['a,] Vector<T>, VectorViewMut<'a, T>;
+    ['a,] VectorViewMut<'a, T>, Vector<T>;
+    ['a, 'b,] VectorViewMut<'a, T>, VectorView<'b, T>;
+    ['a, 'b,] VectorViewMut<'a, T>, VectorViewMut<'b, T>;
+}
+
+macro_rules! impl_view_scalar_ops {
+    ($( [$($lt:tt)*] $view:ty; )+) => { $(
+        impl<'x, 'y, $($lt)* T> Mul<&'y Matrix<T>> for &'x $view
+            where T: Copy + Mul<T, Output=T> + Add<T, Output=T> + Parameter
+        {
+            type Output = Vector<T>;
+            fn mul(self, other_m: &'y Matrix<T>) -> Vector<T> {
+                self.try_mul_mat(other_m).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+    )+ }
+}
+
+impl_view_scalar_ops! {
+    ['a,] VectorView<'a, T>;
+    ['a,] VectorViewMut<'a, T>;
+}
+
+impl<'a, T> MulAssign<T> for VectorViewMut<'a, T>
+    where T: Copy + MulAssign<T> + Parameter
+{
+    fn mul_assign(&mut self, scalar: T) {
+        self.try_mul_assign_scl(scalar).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<'a, T> DivAssign<T> for VectorViewMut<'a, T>
+    where T: Copy + DivAssign<T> + Parameter
+{
+    fn div_assign(&mut self, scalar: T) {
+        self.try_div_assign_scl(scalar).unwrap_or_else(|e| panic!("{}", e))
+    }
+}